@@ -0,0 +1,162 @@
+use chrono::{DateTime, Datelike, Utc};
+
+pub const DEFAULT_FORMAT: &str = "YYYY.0M.MICRO";
+
+const SEPARATORS: [char; 3] = ['.', '-', '_'];
+const MICRO: &str = "MICRO";
+const WEEK_TOKENS: [&str; 2] = ["WW", "0W"];
+
+/// Renders a date based token of the format, returning `None` if the token isn't date based.
+/// Formats containing a week use the ISO week's year, so the first days of January can belong to
+/// the last week of the previous year and vice versa
+fn render_date_token(token: &str, date: &DateTime<Utc>, weekly: bool) -> Option<String> {
+    let year = match weekly {
+        true => date.iso_week().year(),
+        false => date.year(),
+    };
+    let short_year = year - 2000;
+    let week = date.iso_week().week();
+    match token {
+        "YYYY" => Some(year.to_string()),
+        "YY" => Some(short_year.to_string()),
+        "0Y" => Some(format!("{short_year:02}")),
+        "MM" => Some(date.month().to_string()),
+        "0M" => Some(format!("{:02}", date.month())),
+        "WW" => Some(week.to_string()),
+        "0W" => Some(format!("{week:02}")),
+        "DD" => Some(date.day().to_string()),
+        "0D" => Some(format!("{:02}", date.day())),
+        _ => None,
+    }
+}
+
+/// Whether the two parts refer to the same period, ignoring any zero padding
+fn is_same_period(rendered: &str, current: &str) -> bool {
+    match (rendered.parse::<u32>(), current.parse::<u32>()) {
+        (Ok(rendered), Ok(current)) => rendered == current,
+        _ => false,
+    }
+}
+
+/// Determines the next calendar version for the `date`. The micro version is incremented when
+/// the current version is within the same period, otherwise it's reset to 0
+pub fn bump_calver(
+    format: &str,
+    current_version: &str,
+    date: &DateTime<Utc>,
+) -> anyhow::Result<String> {
+    let tokens = format.split(SEPARATORS).collect::<Vec<_>>();
+    let weekly = tokens.iter().any(|t| WEEK_TOKENS.contains(t));
+    let separators = format
+        .chars()
+        .filter(|c| SEPARATORS.contains(c))
+        .collect::<Vec<_>>();
+
+    if let Some(token) = tokens
+        .iter()
+        .find(|t| **t != MICRO && render_date_token(t, date, weekly).is_none())
+    {
+        return Err(anyhow::anyhow!(
+            "The token `{token}` within the calver format `{format}` isn't supported"
+        ));
+    }
+
+    let current_parts = current_version.split(SEPARATORS).collect::<Vec<_>>();
+    let same_period = current_parts.len() == tokens.len()
+        && tokens.iter().zip(&current_parts).all(|(token, current)| {
+            render_date_token(token, date, weekly)
+                .is_none_or(|rendered| is_same_period(&rendered, current))
+        });
+
+    let current_micro = tokens
+        .iter()
+        .position(|t| *t == MICRO)
+        .and_then(|i| current_parts.get(i))
+        .and_then(|micro| micro.parse::<u64>().ok());
+
+    let micro = match (same_period, current_micro) {
+        (true, Some(micro)) => micro + 1,
+        (true, None) => {
+            return Err(anyhow::anyhow!(
+                "A release has already been made for this period and the calver format `{format}` doesn't contain `{MICRO}`"
+            ));
+        }
+        (false, _) => 0,
+    };
+
+    let mut version = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            version.push(separators[i - 1]);
+        }
+        match render_date_token(token, date, weekly) {
+            Some(rendered) => version.push_str(&rendered),
+            None => version.push_str(&micro.to_string()),
+        }
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::new_period(DEFAULT_FORMAT, "2024.05.3", "2024.06.0")]
+    #[case::same_period(DEFAULT_FORMAT, "2024.06.3", "2024.06.4")]
+    #[case::same_period_without_padding("YYYY.MM.MICRO", "2024.6.3", "2024.6.4")]
+    #[case::new_year("YYYY.MICRO", "2023.8", "2024.0")]
+    #[case::short_year("YY.0M.MICRO", "24.06.0", "24.06.1")]
+    #[case::padded_short_year("0Y.MM.DD", "24.6.14", "24.6.15")]
+    #[case::week("YYYY-0W-MICRO", "2024-24-1", "2024-24-2")]
+    #[case::day("YYYY.0M.0D_MICRO", "2024.06.14_2", "2024.06.15_0")]
+    #[case::initial_version(DEFAULT_FORMAT, "0.0.0", "2024.06.0")]
+    fn test_bump_calver(#[case] format: &str, #[case] current: &str, #[case] expected: &str) {
+        let date = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+
+        let result = bump_calver(format, current, &date).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::week_belonging_to_next_year("2024-12-30", "YYYY.WW.MICRO", "2024.52.0", "2025.1.0")]
+    #[case::week_belonging_to_previous_year(
+        "2027-01-01",
+        "YYYY.0W.MICRO",
+        "2026.52.0",
+        "2026.53.0"
+    )]
+    #[case::month_uses_calendar_year("2024-12-30", "YYYY.0M.MICRO", "2024.11.0", "2024.12.0")]
+    fn test_bump_calver_at_year_boundaries(
+        #[case] date: &str,
+        #[case] format: &str,
+        #[case] current: &str,
+        #[case] expected: &str,
+    ) {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc();
+
+        let result = bump_calver(format, current, &date).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case::unknown_token("YYYY.PATCH", "2024.1")]
+    #[case::same_period_without_micro("YYYY.0M.0D", "2024.06.15")]
+    fn test_bump_calver_errors(#[case] format: &str, #[case] current: &str) {
+        let date = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+
+        let result = bump_calver(format, current, &date);
+
+        assert!(result.is_err());
+    }
+}
@@ -1,58 +1,113 @@
 use crate::changeset::{Change, IncrementType};
 use crate::utils;
 use chrono::DateTime;
-use semver::Version;
 
 pub const CHANGELOG_FILENAME: &str = "CHANGELOG.md";
+const SHORT_SHA_LENGTH: usize = 7;
+/// The format of the date within each release heading, e.g. `## 1.2.3 (01-01-1970)`
+const PUBLISH_DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// Options affecting how the changelog is rendered
+#[derive(Debug, Default)]
+pub struct ChangelogOptions {
+    /// The web URL of the repository, used to link to commits
+    pub repository_url: Option<String>,
+    /// Whether changes which don't bump the version are listed within an "Internal" section
+    pub include_internal: bool,
+}
 
 pub fn generate_changelog_contents(
-    next_version: &Version,
+    next_version: &str,
     changesets: &[Change],
     publish_date: DateTime<chrono::Utc>,
+    options: &ChangelogOptions,
 ) -> String {
     if changesets.is_empty() {
         return String::new();
     }
 
-    let publish_date = publish_date.format("%d-%m-%Y").to_string();
+    let publish_date = publish_date.format(PUBLISH_DATE_FORMAT).to_string();
 
     let mut contents = format!("## {next_version} ({publish_date})\n\n");
 
-    let breaking_changes = changesets
-        .iter()
-        .filter(|c| c.bump_type == IncrementType::Major)
+    let mut sections = vec![
+        ("Breaking Changes", IncrementType::Major),
+        ("Features", IncrementType::Minor),
+        ("Patches", IncrementType::Patch),
+    ];
+    if options.include_internal {
+        sections.push(("Internal", IncrementType::None));
+    }
+
+    let sections = sections
+        .into_iter()
+        .filter_map(|(title, bump_type)| {
+            let changes = changesets
+                .iter()
+                .filter(|c| c.bump_type == bump_type)
+                .collect::<Vec<_>>();
+            generate_section(title, changes, options.repository_url.as_deref())
+        })
         .collect::<Vec<_>>();
-    let features = changesets
+    contents.push_str(&sections.join("\n\n"));
+
+    contents
+}
+
+/// Generates the attribution for a change, e.g. `#123, [abc1234](...), thanks @alice`
+fn generate_attribution(change: &Change, repository_url: Option<&str>) -> Option<String> {
+    let mut parts = change
+        .pr
         .iter()
-        .filter(|c| c.bump_type == IncrementType::Minor)
+        .chain(&change.issues)
+        .map(|number| format!("#{number}"))
         .collect::<Vec<_>>();
-    let patches = changesets
+
+    if let Some(commit) = change.introduced_in() {
+        let short_sha = &commit.sha[..commit.sha.len().min(SHORT_SHA_LENGTH)];
+        match repository_url {
+            Some(url) => parts.push(format!("[{short_sha}]({url}/commit/{})", commit.sha)),
+            None => parts.push(short_sha.to_string()),
+        }
+    }
+
+    // Only the authors written within the changeset are usernames, and even then names
+    // containing spaces are the author's name
+    let mut authors = change
+        .authors
         .iter()
-        .filter(|c| c.bump_type == IncrementType::Patch)
+        .map(|author| match author.contains(char::is_whitespace) {
+            true => author.clone(),
+            false => format!("@{author}"),
+        })
         .collect::<Vec<_>>();
-
-    if let Some(section) = generate_section("Breaking Changes", breaking_changes) {
-        contents.push_str(&section);
-        if !features.is_empty() || !patches.is_empty() {
-            contents.push_str("\n\n");
+    for author in &change.git_authors {
+        if !authors.contains(author) {
+            authors.push(author.clone());
         }
     }
 
-    if let Some(section) = generate_section("Features", features) {
-        contents.push_str(&section);
-        if !patches.is_empty() {
-            contents.push_str("\n\n");
+    // Falls back to the git authors of the commits when the changeset doesn't credit anyone
+    if authors.is_empty() {
+        for commit in &change.commits {
+            if !authors.contains(&commit.author) {
+                authors.push(commit.author.clone());
+            }
         }
     }
 
-    if let Some(section) = generate_section("Patches", patches) {
-        contents.push_str(&section);
+    if !authors.is_empty() {
+        parts.push(format!("thanks {}", authors.join(", ")));
     }
 
-    contents
+    (!parts.is_empty()).then(|| parts.join(", "))
 }
 
-fn generate_section(title: &str, changes: Vec<&Change>) -> Option<String> {
+fn generate_section(
+    title: &str,
+    changes: Vec<&Change>,
+    repository_url: Option<&str>,
+) -> Option<String> {
     if changes.is_empty() {
         return None;
     }
@@ -62,6 +117,9 @@ fn generate_section(title: &str, changes: Vec<&Change>) -> Option<String> {
         .iter()
         .map(|c| {
             let mut contents = format!("#### {}", c.summary);
+            if let Some(attribution) = generate_attribution(c, repository_url) {
+                contents.push_str(&format!(" ({attribution})"));
+            }
             if !c.description.is_empty() {
                 contents.push_str("\n\n");
                 contents.push_str(&c.description);
@@ -78,9 +136,10 @@ fn generate_section(title: &str, changes: Vec<&Change>) -> Option<String> {
 /// Creates, or updates a CHANGELOG.md file with the contents of the changesets
 pub fn generate_changelog(
     existing_changelog: &str,
-    next_version: &Version,
+    next_version: &str,
     changesets: &[Change],
     publish_date: DateTime<chrono::Utc>,
+    options: &ChangelogOptions,
 ) -> anyhow::Result<String> {
     let mut contents = String::new();
     if existing_changelog.is_empty() {
@@ -91,7 +150,7 @@ pub fn generate_changelog(
     contents.push_str("\n\n");
 
     let mut contents_to_insert =
-        generate_changelog_contents(next_version, changesets, publish_date);
+        generate_changelog_contents(next_version, changesets, publish_date, options);
     contents_to_insert.push_str("\n\n");
 
     let mut new_contents = utils::insert_before(&contents, "## ", &contents_to_insert);
@@ -101,10 +160,55 @@ pub fn generate_changelog(
     Ok(new_contents)
 }
 
+/// The version of a release's heading, e.g. `1.2.3` for `## 1.2.3 (01-01-1970)`. `None` for any
+/// other heading, such as a `## ` heading within a changeset's description
+fn parse_release_heading(line: &str) -> Option<&str> {
+    let (version, date) = line.strip_prefix("## ")?.split_once(' ')?;
+    let date = date.trim_end().strip_prefix('(')?.strip_suffix(')')?;
+    chrono::NaiveDate::parse_from_str(date, PUBLISH_DATE_FORMAT).ok()?;
+    (!version.is_empty()).then_some(version)
+}
+
+/// Removes the newest release section from the changelog, which must be for `version`
+pub fn remove_latest_release(existing_changelog: &str, version: &str) -> anyhow::Result<String> {
+    let lines = existing_changelog.lines().collect::<Vec<_>>();
+    let is_release_heading = |line: &&str| parse_release_heading(line).is_some();
+
+    let start = lines
+        .iter()
+        .position(is_release_heading)
+        .ok_or(anyhow::anyhow!(
+            "The {CHANGELOG_FILENAME} doesn't contain any releases"
+        ))?;
+    let latest_version = parse_release_heading(lines[start]).unwrap_or_default();
+    if latest_version != version {
+        return Err(anyhow::anyhow!(
+            "The newest release within the {CHANGELOG_FILENAME} is {latest_version} rather than {version}"
+        ));
+    }
+    let end = lines[start + 1..]
+        .iter()
+        .position(is_release_heading)
+        .map_or(lines.len(), |index| start + 1 + index);
+
+    let mut contents = lines[..start]
+        .iter()
+        .chain(&lines[end..])
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string();
+    contents.push('\n');
+
+    Ok(contents)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::changeset::IncrementType;
+    use crate::git::Commit;
     use pretty_assertions::assert_eq;
     use rstest::rstest;
     use std::path::PathBuf;
@@ -117,6 +221,7 @@ mod tests {
             summary: "Breaking change 1".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Breaking change 1")]
     #[case(vec![
@@ -125,6 +230,7 @@ mod tests {
             summary: "Breaking change 1".to_string(),
             description: "This is the text for the breaking change".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Breaking change 1\n\nThis is the text for the breaking change")]
     #[case(vec![
@@ -133,6 +239,7 @@ mod tests {
             summary: "Feature 1".to_string(),
             description: "feature description".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Features\n\n#### Feature 1\n\nfeature description")]
     #[case(vec![
@@ -141,6 +248,7 @@ mod tests {
             summary: "Patch 1".to_string(),
             description: "patch description".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Patch 1\n\npatch description")]
     #[case(vec![
@@ -149,12 +257,14 @@ mod tests {
             summary: "Breaking change 1".to_string(),
             description: "This is the text for the breaking change".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Patch,
             summary: "Patch 1".to_string(),
             description: "This is the text for the patch".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Breaking change 1\n\nThis is the text for the breaking change\n\n### Patches\n\n#### Patch 1\n\nThis is the text for the patch")]
     #[case(vec![
@@ -163,18 +273,21 @@ mod tests {
             summary: "Breaking change 1".to_string(),
             description: "This is the text for the breaking change".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Minor,
             summary: "Feature 1".to_string(),
             description: "This is the text for the feature".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Patch,
             summary: "Patch 1".to_string(),
             description: "This is the text for the patch".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Breaking change 1\n\nThis is the text for the breaking change\n\n### Features\n\n#### Feature 1\n\nThis is the text for the feature\n\n### Patches\n\n#### Patch 1\n\nThis is the text for the patch")]
     #[case(vec![
@@ -183,20 +296,49 @@ mod tests {
             summary: "Breaking change 1".to_string(),
             description: "This is the text for the breaking change".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Major,
             summary: "Breaking change 2".to_string(),
             description: "This is the text for the breaking change again".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], "## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Breaking change 1\n\nThis is the text for the breaking change\n\n#### Breaking change 2\n\nThis is the text for the breaking change again")]
+    #[case(vec![
+        Change {
+            bump_type: IncrementType::Patch,
+            summary: "Patch 1".to_string(),
+            authors: vec!["alice".to_string()],
+            pr: Some(123),
+            ..Default::default()
+        },
+        Change {
+            bump_type: IncrementType::Patch,
+            summary: "Patch 2".to_string(),
+            authors: vec!["bob".to_string(), "Carol Smith".to_string()],
+            issues: vec![45, 67],
+            ..Default::default()
+        },
+        Change {
+            bump_type: IncrementType::Patch,
+            summary: "Patch 3".to_string(),
+            git_authors: vec!["dave".to_string()],
+            ..Default::default()
+        },
+    ], "## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Patch 1 (#123, thanks @alice)\n\n#### Patch 2 (#45, #67, thanks @bob, Carol Smith)\n\n#### Patch 3 (thanks dave)")]
     fn test_generate_changelog_contents(#[case] changes: Vec<Change>, #[case] expected: &str) {
-        let version = Version::new(1, 2, 3);
+        let version = "1.2.3";
 
         let publish_date = chrono::DateTime::from_timestamp(0, 0).unwrap();
 
-        let changelog_contents = generate_changelog_contents(&version, &changes, publish_date);
+        let changelog_contents = generate_changelog_contents(
+            version,
+            &changes,
+            publish_date,
+            &ChangelogOptions::default(),
+        );
 
         assert_eq!(changelog_contents, expected);
     }
@@ -207,12 +349,14 @@ mod tests {
         summary: "test".to_string(),
         description: "".to_string(),
         file_path: PathBuf::new(),
+        ..Default::default()
     }], "# Changelog\n", "# Changelog\n\n## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### test\n")]
     #[case(vec![Change {
         bump_type: IncrementType::Major,
         summary: "test".to_string(),
         description: "".to_string(),
         file_path: PathBuf::new(),
+        ..Default::default()
     }], "# Changelog\n\n## 1.2.2\n\n### Breaking Changes\n\n#### test\n", "# Changelog\n\n## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### test\n\n## 1.2.2\n\n### Breaking Changes\n\n#### test\n")]
     fn test_generate_changelog_generates_correct_contents(
         #[case] changes: Vec<Change>,
@@ -222,12 +366,110 @@ mod tests {
         let publish_date = chrono::DateTime::from_timestamp(0, 0).unwrap();
         let changelog = generate_changelog(
             existing_changelog,
-            &Version::new(1, 2, 3),
+            "1.2.3",
             &changes,
             publish_date,
+            &ChangelogOptions::default(),
         )
         .unwrap();
 
         assert_eq!(changelog, expected);
     }
+
+    #[rstest]
+    #[case::with_repository(
+        Some("https://github.com/owner/repo"),
+        "#### Patch 1 ([0123456](https://github.com/owner/repo/commit/0123456789abcdef), thanks Alice Smith, bob)"
+    )]
+    #[case::without_repository(None, "#### Patch 1 (0123456, thanks Alice Smith, bob)")]
+    fn test_generate_changelog_contents_links_commits(
+        #[case] repository_url: Option<&str>,
+        #[case] expected_entry: &str,
+    ) {
+        let commit = |author: &str| Commit {
+            sha: "0123456789abcdef".to_string(),
+            author: author.to_string(),
+            date: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+        };
+        let changes = vec![Change {
+            bump_type: IncrementType::Patch,
+            summary: "Patch 1".to_string(),
+            commits: vec![commit("Alice Smith"), commit("bob"), commit("Alice Smith")],
+            ..Default::default()
+        }];
+        let publish_date = chrono::DateTime::from_timestamp(0, 0).unwrap();
+
+        let options = ChangelogOptions {
+            repository_url: repository_url.map(String::from),
+            ..Default::default()
+        };
+
+        let contents = generate_changelog_contents("1.2.3", &changes, publish_date, &options);
+
+        assert_eq!(
+            contents,
+            format!("## 1.2.3 (01-01-1970)\n\n### Patches\n\n{expected_entry}")
+        );
+    }
+
+    #[rstest]
+    #[case::excluded(false, "## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Patch 1")]
+    #[case::included(
+        true,
+        "## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Patch 1\n\n### Internal\n\n#### Refactored the parser"
+    )]
+    fn test_generate_changelog_contents_internal_section(
+        #[case] include_internal: bool,
+        #[case] expected: &str,
+    ) {
+        let changes = vec![
+            Change {
+                bump_type: IncrementType::None,
+                summary: "Refactored the parser".to_string(),
+                ..Default::default()
+            },
+            Change {
+                bump_type: IncrementType::Patch,
+                summary: "Patch 1".to_string(),
+                ..Default::default()
+            },
+        ];
+        let options = ChangelogOptions {
+            include_internal,
+            ..Default::default()
+        };
+        let publish_date = chrono::DateTime::from_timestamp(0, 0).unwrap();
+
+        let contents = generate_changelog_contents("1.2.3", &changes, publish_date, &options);
+
+        assert_eq!(contents, expected);
+    }
+
+    #[rstest]
+    #[case::with_older_releases(
+        "# Changelog\n\n## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Fix\n\n## 1.2.2 (01-01-1970)\n\n### Patches\n\n#### Older\n",
+        "# Changelog\n\n## 1.2.2 (01-01-1970)\n\n### Patches\n\n#### Older\n"
+    )]
+    #[case::description_with_headings(
+        "# Changelog\n\n## 1.2.3 (01-01-1970)\n\n### Breaking Changes\n\n#### Removed v1\n\n## Migration guide\n\nUse v2.\n\n## 1.2.2 (01-01-1970)\n\n### Patches\n\n#### Older\n",
+        "# Changelog\n\n## 1.2.2 (01-01-1970)\n\n### Patches\n\n#### Older\n"
+    )]
+    #[case::only_release(
+        "# Changelog\n\n## 1.2.3 (01-01-1970)\n\n### Patches\n\n#### Fix\n",
+        "# Changelog\n"
+    )]
+    fn test_remove_latest_release(#[case] existing_changelog: &str, #[case] expected: &str) {
+        let changelog = remove_latest_release(existing_changelog, "1.2.3").unwrap();
+
+        assert_eq!(changelog, expected);
+    }
+
+    #[rstest]
+    #[case::newer_release("# Changelog\n\n## 1.2.4 (01-01-1970)\n\n## 1.2.3 (01-01-1970)\n")]
+    #[case::no_releases("# Changelog\n")]
+    fn test_remove_latest_release_errors(#[case] existing_changelog: &str) {
+        let result = remove_latest_release(existing_changelog, "1.2.3");
+
+        assert!(result.is_err());
+    }
 }
@@ -1,10 +1,21 @@
+use crate::config::{
+    CHANGESET_DIRECTORY, CONFIG_FILENAME, ChangesetsConfig, NamingStrategy, SortOrder,
+};
+use crate::git;
+use crate::lint::Diagnostic;
+use crate::template::TEMPLATES_DIRECTORY;
+use crate::utils;
 use anyhow::Result;
 use rand::prelude::*;
 use semver::Version;
-use std::io::Read;
-use std::{fmt::Display, io::Write, path::PathBuf};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-const NAMES: [&str; 39] = [
+const NAMES: [&str; 38] = [
     "dog",
     "arnold",
     "cat",
@@ -33,7 +44,6 @@ const NAMES: [&str; 39] = [
     "dolphin",
     "shark",
     "fish",
-    "whale",
     "octopus",
     "squid",
     "jellyfish",
@@ -47,14 +57,31 @@ const NAMES: [&str; 39] = [
 ];
 
 const CHANGE_NAME_PARTS: i8 = 3;
-const CHANGESET_DIRECTORY: &str = ".changeset";
+const MAX_SLUG_LENGTH: usize = 50;
+/// The number of names tried before giving up on finding a unique changeset file name
+const MAX_NAME_ATTEMPTS: usize = 10;
+const FRONTMATTER_DELIMITER: &str = "---";
 const CHANGESET_FILE_KEY: &str = "changeset/type";
+const CHANGESET_PACKAGE_KEY: &str = "changeset/package";
+const CHANGESET_AUTHORS_KEY: &str = "changeset/authors";
+const CHANGESET_GIT_AUTHORS_KEY: &str = "changeset/git-authors";
+const CHANGESET_PR_KEY: &str = "changeset/pr";
+const CHANGESET_ISSUES_KEY: &str = "changeset/issues";
+const CHANGESET_ORDER_KEY: &str = "changeset/order";
+const CHANGESET_VERSION_KEY: &str = "changeset/version";
+const CHANGESET_PREVIOUS_VERSION_KEY: &str = "changeset/previous-version";
+/// The subdirectory of the changeset directory which consumed changesets are archived within
+pub const ARCHIVE_DIRECTORY: &str = "archive";
 
-#[derive(Debug, PartialEq, Clone, Eq)]
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
 pub enum IncrementType {
     Major,
     Minor,
+    #[default]
     Patch,
+    /// An internal change, such as a refactor, which doesn't release a new version
+    None,
 }
 
 pub trait ParseBumpType {
@@ -67,6 +94,7 @@ impl ParseBumpType for str {
             "major" => Ok(IncrementType::Major),
             "minor" => Ok(IncrementType::Minor),
             "patch" => Ok(IncrementType::Patch),
+            "none" => Ok(IncrementType::None),
             _ => Err(anyhow::anyhow!("invalid bump type")),
         }
     }
@@ -78,6 +106,7 @@ impl Display for IncrementType {
             IncrementType::Major => write!(f, "major"),
             IncrementType::Minor => write!(f, "minor"),
             IncrementType::Patch => write!(f, "patch"),
+            IncrementType::None => write!(f, "none"),
         }
     }
 }
@@ -97,7 +126,10 @@ impl Ord for IncrementType {
             (IncrementType::Minor, IncrementType::Major) => std::cmp::Ordering::Less,
             (IncrementType::Minor, _) => std::cmp::Ordering::Greater,
             (IncrementType::Patch, IncrementType::Patch) => std::cmp::Ordering::Equal,
+            (IncrementType::Patch, IncrementType::None) => std::cmp::Ordering::Greater,
             (IncrementType::Patch, _) => std::cmp::Ordering::Less,
+            (IncrementType::None, IncrementType::None) => std::cmp::Ordering::Equal,
+            (IncrementType::None, _) => std::cmp::Ordering::Less,
         }
     }
 }
@@ -106,11 +138,14 @@ pub trait Bump {
     fn bump_major(&self) -> Version;
     fn bump_minor(&self) -> Version;
     fn bump_patch(&self) -> Version;
+    /// Internal changes leave the version as is
+    fn bump_none(&self) -> Version;
     fn bump(&self, bump_type: &IncrementType) -> Version {
         match bump_type {
             IncrementType::Major => self.bump_major(),
             IncrementType::Minor => self.bump_minor(),
             IncrementType::Patch => self.bump_patch(),
+            IncrementType::None => self.bump_none(),
         }
     }
 }
@@ -143,103 +178,765 @@ impl Bump for Version {
             build: self.build.clone(),
         }
     }
+    fn bump_none(&self) -> Version {
+        self.clone()
+    }
+}
+
+/// The file formats a changeset can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ChangesetFormat {
+    #[default]
+    Markdown,
+    Toml,
+    Json,
 }
 
-#[derive(Debug, Clone)]
+impl ChangesetFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChangesetFormat::Markdown => "md",
+            ChangesetFormat::Toml => "toml",
+            ChangesetFormat::Json => "json",
+        }
+    }
+
+    /// The format of the changeset file, `None` if the file isn't a changeset
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "md" => Some(ChangesetFormat::Markdown),
+            "toml" => Some(ChangesetFormat::Toml),
+            "json" => Some(ChangesetFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
 pub struct Change {
     pub file_path: PathBuf,
     pub bump_type: IncrementType,
     pub summary: String,
     pub description: String,
+    /// The package the change applies to, for repositories containing multiple packages
+    pub package: Option<String>,
+    /// Who made the change, used to thank them within the changelog
+    pub authors: Vec<String>,
+    /// Who made the change, taken from git. These are names rather than usernames, so unlike
+    /// [`Change::authors`] they aren't mentioned within the changelog
+    pub git_authors: Vec<String>,
+    /// The pull request which made the change
+    pub pr: Option<u64>,
+    /// The issues resolved by the change
+    pub issues: Vec<u64>,
+    /// An explicit position within the changelog, used by [`SortOrder::Order`]
+    pub order: Option<i64>,
+    /// The version the change was released in, stamped when the changeset is archived
+    pub version: Option<String>,
+    /// The version prior to the release, stamped when the changeset is archived
+    pub previous_version: Option<String>,
+    /// The commits which added and later edited the changeset, oldest first. Only populated by
+    /// [`ChangeSetExt::attach_commits`]
+    pub commits: Vec<git::Commit>,
 }
 
-impl TryFrom<PathBuf> for Change {
-    type Error = &'static str;
-
-    fn try_from(val: PathBuf) -> Result<Self, Self::Error> {
-        if val.is_file() {
-            let file = std::fs::File::open(&val);
-            let file = match file {
-                Ok(file) => file,
-                Err(_) => return Err("Failed to open file"),
-            };
-            let mut reader = std::io::BufReader::new(file);
-            let mut contents = String::new();
-            let read_result = reader.read_to_string(&mut contents);
-            if read_result.is_err() {
-                return Err("Failed to read file");
+impl Change {
+    /// The name of the changeset, i.e. the file name without its extension
+    pub fn name(&self) -> String {
+        self.file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// The name the changeset is referred to by, i.e. its path relative to the changeset
+    /// directory without its extension, e.g. `core/fix`
+    pub fn relative_name(&self, directory: &Path) -> String {
+        let path = self
+            .file_path
+            .strip_prefix(directory)
+            .unwrap_or(&self.file_path);
+        path.with_extension("").to_string_lossy().to_string()
+    }
+
+    /// Renders the changeset in the format it's stored on disk
+    pub fn to_markdown(&self) -> String {
+        let mut contents = format!("---\n{CHANGESET_FILE_KEY}: {}\n", self.bump_type);
+        if let Some(package) = &self.package {
+            contents.push_str(&format!("{CHANGESET_PACKAGE_KEY}: {package}\n"));
+        }
+        if !self.authors.is_empty() {
+            let authors = self.authors.join(", ");
+            contents.push_str(&format!("{CHANGESET_AUTHORS_KEY}: {authors}\n"));
+        }
+        if !self.git_authors.is_empty() {
+            let git_authors = self.git_authors.join(", ");
+            contents.push_str(&format!("{CHANGESET_GIT_AUTHORS_KEY}: {git_authors}\n"));
+        }
+        if let Some(pr) = self.pr {
+            contents.push_str(&format!("{CHANGESET_PR_KEY}: {pr}\n"));
+        }
+        if !self.issues.is_empty() {
+            let issues = self
+                .issues
+                .iter()
+                .map(|issue| issue.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            contents.push_str(&format!("{CHANGESET_ISSUES_KEY}: {issues}\n"));
+        }
+        if let Some(order) = self.order {
+            contents.push_str(&format!("{CHANGESET_ORDER_KEY}: {order}\n"));
+        }
+        if let Some(version) = &self.version {
+            contents.push_str(&format!("{CHANGESET_VERSION_KEY}: {version}\n"));
+        }
+        if let Some(previous_version) = &self.previous_version {
+            contents.push_str(&format!(
+                "{CHANGESET_PREVIOUS_VERSION_KEY}: {previous_version}\n"
+            ));
+        }
+        contents.push_str(&format!("---\n\n# {}\n", self.summary));
+        if !self.description.is_empty() {
+            contents.push_str(&format!("\n{}\n", self.description));
+        }
+        contents
+    }
+
+    /// Attributes the change to the current git user, and whatever the current branch refers to
+    pub fn attribute_to_current_user(&mut self) {
+        self.git_authors = git::user_name().into_iter().collect();
+        match git::current_branch().and_then(|b| git::branch_reference(&b)) {
+            Some(git::BranchReference::PullRequest(pr)) => self.pr = Some(pr),
+            Some(git::BranchReference::Issue(issue)) => self.issues = vec![issue],
+            None => {}
+        }
+    }
+
+    /// The commit which added the changeset, `None` if it hasn't been committed yet
+    pub fn introduced_in(&self) -> Option<&git::Commit> {
+        self.commits.first()
+    }
+
+    /// Renders the changeset in the format of its file, defaulting to markdown
+    pub fn to_contents(&self) -> anyhow::Result<String> {
+        let format = ChangesetFormat::from_path(&self.file_path).unwrap_or_default();
+        let contents = match format {
+            ChangesetFormat::Markdown => self.to_markdown(),
+            ChangesetFormat::Toml => toml::to_string(&StructuredChange::from(self))?,
+            ChangesetFormat::Json => {
+                let mut contents = serde_json::to_string_pretty(&StructuredChange::from(self))?;
+                contents.push('\n');
+                contents
             }
+        };
+        Ok(contents)
+    }
+
+    /// Overwrites the changeset file with the current contents of the changeset
+    pub fn save(&self) -> anyhow::Result<()> {
+        std::fs::write(&self.file_path, self.to_contents()?)?;
+        Ok(())
+    }
+}
+
+/// Renders the changes of a single file, which may contain several changes, in the format of
+/// the file
+pub fn render_changeset_file(changes: &[Change]) -> anyhow::Result<String> {
+    let [first, rest @ ..] = changes else {
+        return Ok(String::new());
+    };
+    if rest.is_empty() {
+        return first.to_contents();
+    }
+
+    let structured = || StructuredChanges {
+        changes: changes.iter().map(StructuredChange::from).collect(),
+    };
+    let contents = match ChangesetFormat::from_path(&first.file_path).unwrap_or_default() {
+        // Each change has its own frontmatter, separated by a blank line
+        ChangesetFormat::Markdown => changes
+            .iter()
+            .map(Change::to_markdown)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ChangesetFormat::Toml => toml::to_string(&structured())?,
+        ChangesetFormat::Json => {
+            let mut contents = serde_json::to_string_pretty(&structured())?;
+            contents.push('\n');
+            contents
+        }
+    };
+    Ok(contents)
+}
+
+/// Groups the changes by the file they were loaded from, keeping the order files first appear in
+fn group_by_file(changes: Vec<Change>) -> Vec<(PathBuf, Vec<Change>)> {
+    let mut files: Vec<(PathBuf, Vec<Change>)> = vec![];
+    for change in changes {
+        match files.iter_mut().find(|(path, _)| *path == change.file_path) {
+            Some((_, grouped)) => grouped.push(change),
+            None => files.push((change.file_path.clone(), vec![change])),
+        }
+    }
+    files
+}
+
+/// The fields of a changeset written in a structured format, i.e. TOML or JSON
+#[derive(Debug, Serialize, Deserialize)]
+struct StructuredChange {
+    #[serde(alias = "type")]
+    bump_type: IncrementType,
+    summary: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    git_authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pr: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    issues: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    order: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_version: Option<String>,
+}
+
+/// A structured changeset file containing several changes, under a `changes` array
+#[derive(Debug, Serialize, Deserialize)]
+struct StructuredChanges {
+    changes: Vec<StructuredChange>,
+}
+
+impl StructuredChange {
+    fn into_change(self, file_path: &Path) -> Change {
+        Change {
+            file_path: file_path.to_path_buf(),
+            bump_type: self.bump_type,
+            summary: self.summary.trim().to_string(),
+            description: self.description.trim().to_string(),
+            package: self.package,
+            authors: self.authors,
+            git_authors: self.git_authors,
+            pr: self.pr,
+            issues: self.issues,
+            order: self.order,
+            version: self.version,
+            previous_version: self.previous_version,
+            commits: vec![],
+        }
+    }
+}
+
+impl From<&Change> for StructuredChange {
+    fn from(change: &Change) -> Self {
+        StructuredChange {
+            bump_type: change.bump_type.clone(),
+            summary: change.summary.clone(),
+            description: change.description.clone(),
+            package: change.package.clone(),
+            authors: change.authors.clone(),
+            git_authors: change.git_authors.clone(),
+            pr: change.pr,
+            issues: change.issues.clone(),
+            order: change.order,
+            version: change.version.clone(),
+            previous_version: change.previous_version.clone(),
+        }
+    }
+}
+
+/// Finds the `key: value` line within the frontmatter, returning its index and value
+fn find_frontmatter_value<'a>(frontmatter: &[&'a str], key: &str) -> Option<(usize, &'a str)> {
+    frontmatter.iter().enumerate().find_map(|(index, line)| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?;
+        Some((index, value.trim()))
+    })
+}
+
+/// Parses a comma separated list of values, ignoring any empty entries
+fn parse_list(value: &str) -> Vec<&str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Parses a pull request or issue number, which may be prefixed with `#`
+fn parse_reference(value: &str) -> Option<u64> {
+    value.trim_start_matches('#').parse().ok()
+}
+
+fn is_summary_heading(line: &str) -> bool {
+    line.starts_with("# ") || line.trim_end() == "#"
+}
+
+/// Parses the contents of a changeset file based on its extension, returning every problem found
+/// rather than just the first. A file can contain several changes
+pub fn parse_changeset(file_path: &Path, contents: &str) -> Result<Vec<Change>, Vec<Diagnostic>> {
+    match ChangesetFormat::from_path(file_path).unwrap_or_default() {
+        ChangesetFormat::Markdown => parse_markdown_changeset(file_path, contents),
+        format => parse_structured_changeset(file_path, contents, format),
+    }
+}
+
+/// Deserializes the structured contents, returning the error's message and line on failure
+fn deserialize_structured<T: serde::de::DeserializeOwned>(
+    contents: &str,
+    format: ChangesetFormat,
+) -> Result<T, (String, Option<usize>)> {
+    match format {
+        ChangesetFormat::Toml => toml::from_str::<T>(contents).map_err(|e| {
+            let line = e
+                .span()
+                .map(|span| contents[..span.start].matches('\n').count() + 1);
+            (e.message().to_string(), line)
+        }),
+        _ => serde_json::from_str::<T>(contents).map_err(|e| {
+            // The line is reported separately, so it's removed from the message
+            let message = e.to_string();
+            let message = message
+                .rsplit_once(" at line ")
+                .map_or(&*message, |(m, _)| m);
+            (message.to_string(), Some(e.line()))
+        }),
+    }
+}
+
+fn parse_structured_changeset(
+    file_path: &Path,
+    contents: &str,
+    format: ChangesetFormat,
+) -> Result<Vec<Change>, Vec<Diagnostic>> {
+    let error = |message: String, line: Option<usize>| {
+        Diagnostic::error(message, Some(file_path.to_path_buf()), line)
+    };
+
+    // Files containing several changes list them within a `changes` array
+    let has_changes = match format {
+        ChangesetFormat::Toml => contents
+            .parse::<toml::Table>()
+            .is_ok_and(|table| table.contains_key("changes")),
+        _ => serde_json::from_str::<serde_json::Value>(contents)
+            .is_ok_and(|value| value.get("changes").is_some()),
+    };
+    let parsed = match has_changes {
+        true => deserialize_structured::<StructuredChanges>(contents, format)
+            .map(|parsed| parsed.changes),
+        false => {
+            deserialize_structured::<StructuredChange>(contents, format).map(|parsed| vec![parsed])
+        }
+    }
+    .map_err(|(message, line)| vec![error(message, line)])?;
+
+    if parsed.is_empty() {
+        return Err(vec![error(
+            "The changeset has no changes".to_string(),
+            None,
+        )]);
+    }
+    let diagnostics = parsed
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| change.summary.trim().is_empty())
+        .map(|(index, _)| match has_changes {
+            true => error(
+                format!("The summary of change {} is empty", index + 1),
+                None,
+            ),
+            false => error("The summary is empty".to_string(), None),
+        })
+        .collect::<Vec<_>>();
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    Ok(parsed
+        .into_iter()
+        .map(|change| change.into_change(file_path))
+        .collect())
+}
 
-            let metadata = contents
-                .lines()
-                .find(|line| line.starts_with(CHANGESET_FILE_KEY));
-
-            match metadata {
-                Some(metadata) => {
-                    let metadata = metadata.split(":").collect::<Vec<&str>>();
-                    let bump_type = metadata[1].trim();
-                    let parsed_bump_type = bump_type.parse_bump_type();
-                    if parsed_bump_type.is_err() {
-                        return Err("Invalid bump type found in file");
-                    }
-                    let parsed_bump_type = parsed_bump_type.unwrap();
-
-                    let summary = contents
-                        .lines()
-                        .find(|line| line.starts_with("# "))
-                        .unwrap()
-                        .replace("# ", "");
-
-                    let description = contents
-                        .split("\n---\n")
-                        .skip(2)
-                        .skip_while(|line| line.starts_with("# "))
-                        .collect::<Vec<&str>>()
-                        .join("\n");
-
-                    return Ok(Change {
-                        bump_type: parsed_bump_type,
-                        summary,
-                        description,
-                        file_path: val.clone(),
-                    });
+/// The index of the first line of each change within a markdown changeset. Every change starts
+/// with its own frontmatter, so a `---` line is only treated as the start of another change when
+/// it's followed by a `changeset/` key, rather than being a horizontal rule within a description
+fn markdown_change_starts(lines: &[&str]) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut in_frontmatter = lines
+        .first()
+        .is_some_and(|line| line.trim() == FRONTMATTER_DELIMITER);
+
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        if in_frontmatter {
+            in_frontmatter = line.trim() != FRONTMATTER_DELIMITER;
+        } else if line.trim() == FRONTMATTER_DELIMITER
+            && lines
+                .get(index + 1)
+                .is_some_and(|next| next.starts_with("changeset/"))
+        {
+            starts.push(index);
+            in_frontmatter = true;
+        }
+    }
+    starts
+}
+
+fn parse_markdown_changeset(
+    file_path: &Path,
+    contents: &str,
+) -> Result<Vec<Change>, Vec<Diagnostic>> {
+    let lines = contents.lines().collect::<Vec<_>>();
+    let starts = markdown_change_starts(&lines);
+    let mut changes = vec![];
+    let mut diagnostics = vec![];
+
+    for (index, start) in starts.iter().enumerate() {
+        let end = starts.get(index + 1).copied().unwrap_or(lines.len());
+        match parse_markdown_change(file_path, &lines[*start..end], *start) {
+            Ok(change) => changes.push(change),
+            Err(problems) => diagnostics.extend(problems),
+        }
+    }
+
+    match diagnostics.is_empty() {
+        true => Ok(changes),
+        false => Err(diagnostics),
+    }
+}
+
+/// Parses a single change within a markdown changeset, whose lines start at `offset` within the
+/// file
+fn parse_markdown_change(
+    file_path: &Path,
+    lines: &[&str],
+    offset: usize,
+) -> Result<Change, Vec<Diagnostic>> {
+    let error = |message: String, line: Option<usize>| {
+        Diagnostic::error(
+            message,
+            Some(file_path.to_path_buf()),
+            line.map(|line| line + offset),
+        )
+    };
+    let mut diagnostics = vec![];
+
+    // The frontmatter is delimited by `---` lines at the very start of the file
+    let frontmatter_end = match lines.first() {
+        Some(line) if line.trim() == FRONTMATTER_DELIMITER => lines
+            .iter()
+            .skip(1)
+            .position(|line| line.trim() == FRONTMATTER_DELIMITER)
+            .map(|index| index + 1),
+        _ => None,
+    };
+    let (frontmatter, body_start) = match frontmatter_end {
+        Some(end) => (&lines[1..end], end + 1),
+        None => {
+            diagnostics.push(error(
+                format!("Missing the `{FRONTMATTER_DELIMITER}` delimited frontmatter at the start of the file"),
+                Some(1),
+            ));
+            (&lines[0..0], 0)
+        }
+    };
+
+    let bump_type = match find_frontmatter_value(frontmatter, CHANGESET_FILE_KEY) {
+        // Offset by the opening delimiter, and as line numbers start at 1
+        Some((index, value)) => {
+            match value.parse_bump_type() {
+                Ok(bump_type) => Some(bump_type),
+                Err(_) => {
+                    diagnostics.push(error(
+                    format!("Unknown bump type `{value}`, expected `major`, `minor`, `patch` or `none`"),
+                    Some(index + 2),
+                ));
+                    None
                 }
-                None => return Err("No metadata found in file"),
             }
         }
-        return Err("Path is not a file");
+        None => {
+            if frontmatter_end.is_some() {
+                diagnostics.push(error(
+                    format!("Missing `{CHANGESET_FILE_KEY}` within the frontmatter"),
+                    Some(1),
+                ));
+            }
+            None
+        }
+    };
+
+    let summary_index = lines
+        .iter()
+        .skip(body_start)
+        .position(|line| is_summary_heading(line))
+        .map(|index| index + body_start);
+    let summary = match summary_index {
+        Some(index) => {
+            let summary = lines[index].trim_start_matches('#').trim();
+            if summary.is_empty() {
+                diagnostics.push(error("The summary is empty".to_string(), Some(index + 1)));
+            }
+            summary
+        }
+        None => {
+            diagnostics.push(error(
+                "Missing a `# ` heading containing the summary".to_string(),
+                None,
+            ));
+            ""
+        }
+    };
+
+    // Everything after the summary is surfaced as the description
+    let description = summary_index
+        .map(|index| lines[index + 1..].join("\n").trim().to_string())
+        .unwrap_or_default();
+
+    let optional_value = |key: &str| {
+        find_frontmatter_value(frontmatter, key)
+            .map(|(_, value)| value.to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let package = optional_value(CHANGESET_PACKAGE_KEY);
+    let version = optional_value(CHANGESET_VERSION_KEY);
+    let previous_version = optional_value(CHANGESET_PREVIOUS_VERSION_KEY);
+
+    let list_value = |key: &str| {
+        find_frontmatter_value(frontmatter, key)
+            .map(|(_, value)| parse_list(value).into_iter().map(String::from).collect())
+            .unwrap_or_default()
+    };
+    let authors = list_value(CHANGESET_AUTHORS_KEY);
+    let git_authors = list_value(CHANGESET_GIT_AUTHORS_KEY);
+
+    let pr = match find_frontmatter_value(frontmatter, CHANGESET_PR_KEY) {
+        Some((_, "")) | None => None,
+        Some((index, value)) => {
+            let pr = parse_reference(value);
+            if pr.is_none() {
+                diagnostics.push(error(
+                    format!("The pull request `{value}` isn't a number"),
+                    Some(index + 2),
+                ));
+            }
+            pr
+        }
+    };
+
+    let mut issues = vec![];
+    if let Some((index, value)) = find_frontmatter_value(frontmatter, CHANGESET_ISSUES_KEY) {
+        for issue in parse_list(value) {
+            match parse_reference(issue) {
+                Some(issue) => issues.push(issue),
+                None => diagnostics.push(error(
+                    format!("The issue `{issue}` isn't a number"),
+                    Some(index + 2),
+                )),
+            }
+        }
     }
+
+    let order = match find_frontmatter_value(frontmatter, CHANGESET_ORDER_KEY) {
+        Some((_, "")) | None => None,
+        Some((index, value)) => {
+            let order = value.parse().ok();
+            if order.is_none() {
+                diagnostics.push(error(
+                    format!("The order `{value}` isn't a number"),
+                    Some(index + 2),
+                ));
+            }
+            order
+        }
+    };
+
+    let Some(bump_type) = bump_type.filter(|_| diagnostics.is_empty()) else {
+        return Err(diagnostics);
+    };
+
+    Ok(Change {
+        file_path: file_path.to_path_buf(),
+        bump_type,
+        summary: summary.to_string(),
+        description,
+        package,
+        authors,
+        git_authors,
+        pr,
+        issues,
+        order,
+        version,
+        previous_version,
+        commits: vec![],
+    })
+}
+
+/// Reads the changes within the changeset file
+pub fn read_changeset(path: &Path) -> Result<Vec<Change>, Vec<Diagnostic>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        vec![Diagnostic::error(
+            format!("Failed to read the changeset: {e}"),
+            Some(path.to_path_buf()),
+            None,
+        )]
+    })?;
+
+    parse_changeset(path, &contents)
 }
 
 pub trait ChangeSetExt {
-    fn determine_next_version(&self, current_version: &Version) -> Result<Version>;
     fn determine_final_bump_type(&self) -> Result<Option<IncrementType>>;
-    fn consume(self, current_version: &Version) -> Result<Version>;
+    fn consume(self) -> Result<()>;
+    /// Moves the changesets into `<directory>/archive/<version>/`, stamping the versions into them
+    fn archive(
+        self,
+        config: &ChangesetsConfig,
+        version: &str,
+        previous_version: &str,
+    ) -> Result<()>;
+    /// Moves archived changesets back into the changeset directory, removing their stamped versions
+    fn restore(self, config: &ChangesetsConfig, version: &str) -> Result<()>;
+    /// Looks up the commits which added and edited each changeset within the local repository
+    fn attach_commits(&mut self);
+    /// Sorts the changesets so the changelog is the same regardless of the filesystem. Ties are
+    /// broken by the file path. [`SortOrder::Git`] requires the commits to be attached
+    fn sort_changesets(&mut self, order: SortOrder);
 }
 
 impl ChangeSetExt for Vec<Change> {
-    fn determine_next_version(&self, current_version: &Version) -> Result<Version> {
-        let bump_type = self.determine_final_bump_type()?;
-        match bump_type {
-            Some(bump_type) => Ok(current_version.bump(&bump_type)),
-            None => Ok(current_version.clone()),
+    fn determine_final_bump_type(&self) -> Result<Option<IncrementType>> {
+        // Internal changes don't require a release
+        let max_bump_type = self
+            .iter()
+            .map(|c| &c.bump_type)
+            .filter(|t| **t != IncrementType::None)
+            .max()
+            .cloned();
+
+        Ok(max_bump_type)
+    }
+    fn consume(self) -> Result<()> {
+        // A file containing several changes is only removed once
+        for (path, _) in group_by_file(self) {
+            std::fs::remove_file(path)?;
         }
+
+        Ok(())
     }
-    fn determine_final_bump_type(&self) -> Result<Option<IncrementType>> {
-        if self.is_empty() {
-            return Ok(None);
+    fn archive(
+        self,
+        config: &ChangesetsConfig,
+        version: &str,
+        previous_version: &str,
+    ) -> Result<()> {
+        let archive_directory = archive_directory(config, version)?;
+        let files = group_by_file(self);
+        // Nested changesets keep their place within the directory structure
+        let destinations = files
+            .iter()
+            .map(|(original, _)| {
+                let relative_path = original
+                    .strip_prefix(&config.directory)
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|_| original.file_name().unwrap_or_default().into());
+                archive_directory.join(relative_path)
+            })
+            .collect::<Vec<_>>();
+
+        // Checked upfront so a conflict doesn't leave the changesets partially archived
+        if let Some(((original, _), destination)) = files
+            .iter()
+            .zip(&destinations)
+            .find(|(_, destination)| destination.exists())
+        {
+            return Err(anyhow::anyhow!(
+                "Unable to archive {} as {} already exists",
+                original.display(),
+                destination.display()
+            ));
         }
-        let max_bump_type = self.iter().map(|c| &c.bump_type).max().cloned();
 
-        Ok(max_bump_type)
+        for ((original, mut changes), destination) in files.into_iter().zip(destinations) {
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            for change in &mut changes {
+                change.file_path = destination.clone();
+                change.version = Some(version.to_string());
+                change.previous_version = Some(previous_version.to_string());
+            }
+            std::fs::write(&destination, render_changeset_file(&changes)?)?;
+            std::fs::remove_file(original)?;
+        }
+
+        Ok(())
     }
-    fn consume(self, current_version: &Version) -> Result<Version> {
-        let new_version = self.determine_next_version(current_version)?;
-        self.iter().for_each(|c| {
-            std::fs::remove_file(&c.file_path).unwrap();
-        });
+    fn restore(self, config: &ChangesetsConfig, version: &str) -> Result<()> {
+        let archive_directory = archive_directory(config, version)?;
+        let files = group_by_file(self);
+        let destinations = files
+            .iter()
+            .map(|(path, _)| {
+                let relative_path = path.strip_prefix(&archive_directory)?;
+                Ok(config.directory.join(relative_path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Checked upfront so a conflict doesn't leave the changesets partially restored
+        if let Some(destination) = destinations.iter().find(|d| d.exists()) {
+            return Err(anyhow::anyhow!(
+                "Unable to restore the archived changesets as {} already exists",
+                destination.display()
+            ));
+        }
+
+        for ((original, mut changes), destination) in files.into_iter().zip(destinations) {
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
 
-        Ok(new_version)
+            for change in &mut changes {
+                change.file_path = destination.clone();
+                change.version = None;
+                change.previous_version = None;
+            }
+            std::fs::write(&destination, render_changeset_file(&changes)?)?;
+            std::fs::remove_file(original)?;
+        }
+
+        std::fs::remove_dir_all(archive_directory)?;
+        Ok(())
+    }
+    fn attach_commits(&mut self) {
+        self.iter_mut().for_each(|c| {
+            c.commits = git::commits_for(&c.file_path);
+        });
+    }
+    fn sort_changesets(&mut self, order: SortOrder) {
+        match order {
+            SortOrder::Git => self.sort_by_cached_key(|c| {
+                let date = c.introduced_in().map(|commit| commit.date);
+                (date.is_none(), date, c.file_path.clone())
+            }),
+            SortOrder::Mtime => self.sort_by_cached_key(|c| {
+                let modified = std::fs::metadata(&c.file_path).and_then(|m| m.modified());
+                (modified.ok(), c.file_path.clone())
+            }),
+            SortOrder::Name => {
+                self.sort_by(|a, b| a.name().cmp(&b.name()).then(a.file_path.cmp(&b.file_path)))
+            }
+            SortOrder::Order => {
+                self.sort_by_cached_key(|c| (c.order.is_none(), c.order, c.file_path.clone()))
+            }
+        }
     }
 }
 
@@ -251,45 +948,285 @@ pub fn generate_change_name() -> String {
     return parts.join("-");
 }
 
-pub fn create_change_file(bump_type: IncrementType, message: &str) -> anyhow::Result<PathBuf> {
-    let filename = generate_change_name();
+/// Converts the text into a lowercase, hyphen separated file name
+fn slugify(text: &str) -> String {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    slug.chars()
+        .take(MAX_SLUG_LENGTH)
+        .collect::<String>()
+        .trim_end_matches('-')
+        .to_string()
+}
+
+/// Generates the name of the changeset file according to the naming strategy, falling back to
+/// random words when the strategy can't produce a name
+fn generate_base_name(naming: &NamingStrategy, message: &str) -> String {
+    let name = match naming {
+        NamingStrategy::Words => None,
+        NamingStrategy::Timestamp => Some(format!(
+            "{}-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            generate_change_name()
+        )),
+        NamingStrategy::Slug => Some(slugify(message)),
+        NamingStrategy::Branch => git::current_branch().map(|branch| slugify(&branch)),
+    };
+
+    name.filter(|name| !name.is_empty())
+        .unwrap_or_else(generate_change_name)
+}
+
+/// Creates a new file within the directory, retrying with a different name if the file already exists
+fn create_unique_file(
+    directory: &Path,
+    naming: &NamingStrategy,
+    message: &str,
+    format: ChangesetFormat,
+) -> anyhow::Result<(PathBuf, std::fs::File)> {
+    let base_name = generate_base_name(naming, message);
+
+    for attempt in 0..MAX_NAME_ATTEMPTS {
+        let filename = match (naming, attempt) {
+            (_, 0) => base_name.clone(),
+            (NamingStrategy::Words, _) => generate_change_name(),
+            (_, attempt) => format!("{base_name}-{}", attempt + 1),
+        };
+        let filepath = directory.join(format!("{filename}.{}", format.extension()));
 
-    if !PathBuf::from(CHANGESET_DIRECTORY).exists() {
-        std::fs::create_dir(CHANGESET_DIRECTORY)?;
+        match std::fs::File::create_new(&filepath) {
+            Ok(file) => return Ok((filepath, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
     }
 
-    let filepath = PathBuf::from(CHANGESET_DIRECTORY).join(format!("{}.md", filename));
+    Err(anyhow::anyhow!(
+        "Unable to find a unique name for the changeset after {MAX_NAME_ATTEMPTS} attempts"
+    ))
+}
+
+/// Writes the change to a new uniquely named file within the directory
+pub fn create_change_file(
+    mut change: Change,
+    naming: &NamingStrategy,
+    format: ChangesetFormat,
+    directory: &Path,
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(directory)?;
 
-    let mut file = std::fs::File::create(filepath.clone())?;
+    let (filepath, mut file) = create_unique_file(directory, naming, &change.summary, format)?;
+    change.file_path = filepath.clone();
+    write!(file, "{}", change.to_contents()?)?;
 
-    write!(
-        file,
-        "---\n{CHANGESET_FILE_KEY}: {bump_type}\n---\n\n# {message}\n",
-    )?;
+    Ok(filepath)
+}
+
+/// Writes the change to a new file with the given name, failing if it already exists
+pub fn create_named_change_file(
+    mut change: Change,
+    name: &str,
+    format: ChangesetFormat,
+    directory: &Path,
+) -> anyhow::Result<PathBuf> {
+    utils::validate_file_stem(name)?;
+    std::fs::create_dir_all(directory)?;
+
+    let filepath = directory.join(format!("{name}.{}", format.extension()));
+    let mut file = match std::fs::File::create_new(&filepath) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            return Err(anyhow::anyhow!(
+                "A changeset already exists at {}",
+                filepath.display()
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    change.file_path = filepath.clone();
+    write!(file, "{}", change.to_contents()?)?;
 
     Ok(filepath)
 }
 
-/// Retrieves all changesets from the changeset directory
-pub fn get_changesets() -> anyhow::Result<Vec<Change>> {
+/// Whether the file matches an entry of the ignore list, either by its name or by its path
+/// relative to the changeset directory. Entries can also refer to subdirectories
+fn is_ignored(relative_path: &Path, ignore: &[String]) -> bool {
+    ignore.iter().any(|entry| {
+        let entry = Path::new(entry.trim_end_matches('/'));
+        relative_path.starts_with(entry) || relative_path.file_name() == Some(entry.as_os_str())
+    })
+}
+
+/// Recursively finds the changeset files within the directory, skipping ignored files
+fn find_changeset_files(config: &ChangesetsConfig) -> anyhow::Result<Vec<PathBuf>> {
+    // Compared canonically, as the directory may be configured as `./.changeset` or absolute
+    let config_path = Path::new(CHANGESET_DIRECTORY)
+        .join(CONFIG_FILENAME)
+        .canonicalize()
+        .ok();
+    let is_config = |path: &Path| {
+        path.file_name() == Some(std::ffi::OsStr::new(CONFIG_FILENAME))
+            && config_path.is_some()
+            && path.canonicalize().ok() == config_path
+    };
+    let archive_path = config.directory.join(ARCHIVE_DIRECTORY);
+    let templates_path = config.directory.join(TEMPLATES_DIRECTORY);
+    let mut files = vec![];
+    let mut directories = vec![config.directory.clone()];
+
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(&directory)? {
+            let path = entry?.path();
+            let relative_path = path.strip_prefix(&config.directory).unwrap_or(&path);
+            if is_config(&path)
+                || path == archive_path
+                || path == templates_path
+                || is_ignored(relative_path, &config.ignore)
+            {
+                continue;
+            }
+
+            if path.is_dir() {
+                directories.push(path);
+            } else if ChangesetFormat::from_path(&path).is_some() {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The changeset files which `name` refers to, i.e. those whose path relative to the changeset
+/// directory matches it either with or without the extension, e.g. `core/fix` or `core/fix.toml`.
+/// Invalid changesets are included, as they aren't read
+pub fn find_named_changeset_files(
+    config: &ChangesetsConfig,
+    name: &str,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let name = Path::new(name);
+    let files = find_changeset_files(config)?
+        .into_iter()
+        .filter(|path| {
+            let relative_path = path.strip_prefix(&config.directory).unwrap_or(path);
+            relative_path == name || relative_path.with_extension("") == name
+        })
+        .collect();
+
+    Ok(files)
+}
+
+/// Loads every changeset from the changeset directory, collecting the problems with any invalid
+/// changesets rather than failing on the first
+pub fn load_changesets(
+    config: &ChangesetsConfig,
+) -> anyhow::Result<(Vec<Change>, Vec<Diagnostic>)> {
     let mut changesets: Vec<Change> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
 
-    for entry in std::fs::read_dir(CHANGESET_DIRECTORY)? {
-        let entry = entry?;
-        let path = entry.path();
-        let extension = path.extension();
-        if path.is_file() && extension == Some(std::ffi::OsStr::new("md")) {
-            let change = Change::try_from(path).unwrap();
-            changesets.push(change);
+    for path in find_changeset_files(config)? {
+        match read_changeset(&path) {
+            Ok(changes) => changesets.extend(changes),
+            Err(problems) => diagnostics.extend(problems),
         }
     }
 
+    Ok((changesets, diagnostics))
+}
+
+/// Retrieves all changesets from the changeset directory, failing if any of them are invalid
+pub fn get_changesets(config: &ChangesetsConfig) -> anyhow::Result<Vec<Change>> {
+    let (changesets, diagnostics) = load_changesets(config)?;
+    if !diagnostics.is_empty() {
+        let problems = diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(anyhow::anyhow!(
+            "Invalid changesets were found, run `changeset lint` for more details\n{problems}"
+        ));
+    }
+
     Ok(changesets)
 }
 
+/// The config for loading the changesets archived when `version` was released
+fn archive_config(config: &ChangesetsConfig, version: &str) -> anyhow::Result<ChangesetsConfig> {
+    Ok(ChangesetsConfig {
+        directory: archive_directory(config, version)?,
+        ignore: vec![],
+        ..Default::default()
+    })
+}
+
+/// The directory the changesets released in `version` are archived within. The version is
+/// checked as it may come from a plugin, so it can't point outside of the archive
+fn archive_directory(config: &ChangesetsConfig, version: &str) -> anyhow::Result<PathBuf> {
+    utils::validate_file_stem(version)
+        .map_err(|e| anyhow::anyhow!("The version {version} can't be archived: {e}"))?;
+
+    Ok(config.directory.join(ARCHIVE_DIRECTORY).join(version))
+}
+
+/// Retrieves the changesets which were archived when `version` was released
+pub fn get_archived_changesets(
+    config: &ChangesetsConfig,
+    version: &str,
+) -> anyhow::Result<Vec<Change>> {
+    let archive_config = archive_config(config, version)?;
+    let changesets = match archive_config.directory.exists() {
+        true => get_changesets(&archive_config)?,
+        false => vec![],
+    };
+    if changesets.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No changesets were archived for {version}, only releases made with `changesets.archive` enabled can be reverted"
+        ));
+    }
+
+    Ok(changesets)
+}
+
+/// Finds an archived release which was made after `version`, i.e. whose previous version is
+/// `version`
+pub fn find_release_after(
+    config: &ChangesetsConfig,
+    version: &str,
+) -> anyhow::Result<Option<String>> {
+    let archive_directory = config.directory.join(ARCHIVE_DIRECTORY);
+    if !archive_directory.exists() {
+        return Ok(None);
+    }
+
+    for entry in std::fs::read_dir(archive_directory)? {
+        let release = entry?.file_name().to_string_lossy().to_string();
+        if release == version {
+            continue;
+        }
+        let changesets = get_changesets(&archive_config(config, &release)?)?;
+        if changesets
+            .iter()
+            .any(|c| c.previous_version.as_deref() == Some(version))
+        {
+            return Ok(Some(release));
+        }
+    }
+
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{TimeZone, Utc};
     use pretty_assertions::assert_eq;
     use rstest::rstest;
 
@@ -300,18 +1237,21 @@ mod tests {
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Minor,
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Patch,
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], Some(IncrementType::Major))]
     #[case::minor(vec![
@@ -320,18 +1260,21 @@ mod tests {
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Minor,
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Patch,
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], Some(IncrementType::Minor))]
     #[case::patch(vec![
@@ -340,14 +1283,33 @@ mod tests {
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
         Change {
             bump_type: IncrementType::Patch,
             summary: "".to_string(),
             description: "".to_string(),
             file_path: PathBuf::new(),
+            ..Default::default()
         },
     ], Some(IncrementType::Patch))]
+    #[case::patch_and_none(vec![
+        Change {
+            bump_type: IncrementType::None,
+            ..Default::default()
+        },
+        Change {
+            bump_type: IncrementType::Patch,
+            ..Default::default()
+        },
+    ], Some(IncrementType::Patch))]
+    #[case::only_none(vec![
+        Change {
+            bump_type: IncrementType::None,
+            ..Default::default()
+        },
+    ], None)]
+    #[case::empty(vec![], None)]
     fn test_determine_final_bump_type_selects_correct_bump_type(
         #[case] input: Vec<Change>,
         #[case] expected: Option<IncrementType>,
@@ -355,4 +1317,617 @@ mod tests {
         let result = input.determine_final_bump_type().unwrap();
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case::git(SortOrder::Git, vec!["b", "a", "c"])]
+    #[case::name(SortOrder::Name, vec!["a", "b", "c"])]
+    #[case::order(SortOrder::Order, vec!["c", "a", "b"])]
+    fn test_sort_changesets(#[case] order: SortOrder, #[case] expected: Vec<&str>) {
+        let change = |name: &str, day: Option<u32>, order: Option<i64>| Change {
+            file_path: PathBuf::from(format!(".changeset/{name}.md")),
+            order,
+            commits: day
+                .map(|day| git::Commit {
+                    sha: name.to_string(),
+                    author: "Alice".to_string(),
+                    date: Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap(),
+                })
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let mut changesets = vec![
+            change("c", None, Some(-1)),
+            change("a", Some(2), Some(5)),
+            change("b", Some(1), None),
+        ];
+
+        changesets.sort_changesets(order);
+
+        let names = changesets.iter().map(|c| c.name()).collect::<Vec<_>>();
+        assert_eq!(names, expected);
+    }
+
+    #[rstest]
+    #[case::summary_only(
+        "---\nchangeset/type: minor\n---\n\n# Added a feature\n",
+        "Added a feature",
+        ""
+    )]
+    #[case::with_description(
+        "---\nchangeset/type: minor\n---\n\n# Added a feature\n\nThe feature does things.\n\n## Usage\n\nUse it.\n",
+        "Added a feature",
+        "The feature does things.\n\n## Usage\n\nUse it."
+    )]
+    fn test_read_changeset_parses_summary_and_description(
+        #[case] contents: &str,
+        #[case] expected_summary: &str,
+        #[case] expected_description: &str,
+    ) {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("change.md");
+        std::fs::write(&path, contents).unwrap();
+
+        let change = read_changeset(&path).unwrap().remove(0);
+
+        assert_eq!(change.bump_type, IncrementType::Minor);
+        assert_eq!(change.summary, expected_summary);
+        assert_eq!(change.description, expected_description);
+    }
+
+    #[rstest]
+    #[case("---\nchangeset/type: minor\n---\n\n# Added a feature\n", None)]
+    #[case(
+        "---\nchangeset/type: minor\nchangeset/package: core\n---\n\n# Added a feature\n",
+        Some("core".to_string())
+    )]
+    fn test_read_changeset_parses_package(
+        #[case] contents: &str,
+        #[case] expected: Option<String>,
+    ) {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("change.md");
+        std::fs::write(&path, contents).unwrap();
+
+        let change = read_changeset(&path).unwrap().remove(0);
+
+        assert_eq!(change.package, expected);
+        assert_eq!(change.name(), "change");
+    }
+
+    #[rstest]
+    #[case::toml(
+        "change.toml",
+        "type = \"minor\"\nsummary = \"Added a feature\"\ndescription = \"It does things.\"\nissues = [12]\n"
+    )]
+    #[case::json(
+        "change.json",
+        r#"{"bump_type": "minor", "summary": "Added a feature", "description": "It does things.", "issues": [12]}"#
+    )]
+    fn test_parse_changeset_parses_structured_formats(
+        #[case] file_name: &str,
+        #[case] contents: &str,
+    ) {
+        let change = parse_changeset(Path::new(file_name), contents)
+            .unwrap()
+            .remove(0);
+
+        assert_eq!(change.bump_type, IncrementType::Minor);
+        assert_eq!(change.summary, "Added a feature");
+        assert_eq!(change.description, "It does things.");
+        assert_eq!(change.issues, vec![12]);
+    }
+
+    #[rstest]
+    #[case::toml_unknown_type("change.toml", "type = \"huge\"\nsummary = \"Fixed\"\n", Some(1))]
+    #[case::toml_missing_summary("change.toml", "\n\ntype = \"patch\"\n", Some(1))]
+    #[case::json_syntax("change.json", "{\n  \"type\": \"patch\",\n  \"summary\": }", Some(3))]
+    #[case::json_empty_summary("change.json", r#"{"type": "patch", "summary": " "}"#, None)]
+    fn test_parse_changeset_rejects_invalid_structured_formats(
+        #[case] file_name: &str,
+        #[case] contents: &str,
+        #[case] expected_line: Option<usize>,
+    ) {
+        let diagnostics = parse_changeset(Path::new(file_name), contents).unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, expected_line);
+    }
+
+    #[rstest]
+    #[case::markdown(
+        "change.md",
+        "---\nchangeset/type: minor\n---\n\n# Added lists\n\nLists can be nested.\n\n---\n\nA horizontal rule.\n\n---\nchangeset/type: patch\n---\n\n# Fixed tabs\n"
+    )]
+    #[case::toml(
+        "change.toml",
+        "[[changes]]\ntype = \"minor\"\nsummary = \"Added lists\"\ndescription = \"Lists can be nested.\\n\\n---\\n\\nA horizontal rule.\"\n\n[[changes]]\ntype = \"patch\"\nsummary = \"Fixed tabs\"\n"
+    )]
+    #[case::json(
+        "change.json",
+        r#"{"changes": [{"type": "minor", "summary": "Added lists", "description": "Lists can be nested.\n\n---\n\nA horizontal rule."}, {"type": "patch", "summary": "Fixed tabs"}]}"#
+    )]
+    fn test_parse_changeset_parses_multiple_changes(
+        #[case] file_name: &str,
+        #[case] contents: &str,
+    ) {
+        let changes = parse_changeset(Path::new(file_name), contents).unwrap();
+
+        let changes = changes
+            .iter()
+            .map(|c| {
+                (
+                    c.file_path.as_path(),
+                    c.bump_type.clone(),
+                    c.summary.as_str(),
+                    c.description.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    Path::new(file_name),
+                    IncrementType::Minor,
+                    "Added lists",
+                    "Lists can be nested.\n\n---\n\nA horizontal rule."
+                ),
+                (Path::new(file_name), IncrementType::Patch, "Fixed tabs", ""),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_parse_changeset_reports_lines_of_later_changes() {
+        let contents = "---\nchangeset/type: minor\n---\n\n# Added lists\n\n---\nchangeset/type: huge\n---\n\n# Fixed tabs\n";
+
+        let diagnostics = parse_changeset(Path::new("change.md"), contents).unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(8));
+    }
+
+    #[rstest]
+    fn test_parse_changeset_parses_attribution() {
+        let contents = "---\nchangeset/type: patch\nchangeset/authors: alice, bob\nchangeset/git-authors: Carol Smith\nchangeset/pr: #123\nchangeset/issues: #45, 67\n---\n\n# Fixed a bug\n";
+
+        let change = parse_changeset(Path::new("change.md"), contents)
+            .unwrap()
+            .remove(0);
+
+        assert_eq!(change.authors, vec!["alice", "bob"]);
+        assert_eq!(change.git_authors, vec!["Carol Smith"]);
+        assert_eq!(change.pr, Some(123));
+        assert_eq!(change.issues, vec![45, 67]);
+    }
+
+    #[rstest]
+    #[case::missing_summary(
+        "---\nchangeset/type: minor\n---\n\nNo heading\n",
+        vec![("Missing a `# ` heading containing the summary", None)]
+    )]
+    #[case::empty_summary(
+        "---\nchangeset/type: minor\n---\n\n# \n",
+        vec![("The summary is empty", Some(5))]
+    )]
+    #[case::missing_frontmatter(
+        "# Added a feature\n",
+        vec![("Missing the `---` delimited frontmatter at the start of the file", Some(1))]
+    )]
+    #[case::missing_bump_type(
+        "---\nchangeset/package: core\n---\n\n# Added a feature\n",
+        vec![("Missing `changeset/type` within the frontmatter", Some(1))]
+    )]
+    #[case::unknown_bump_type(
+        "---\nchangeset/package: core\nchangeset/type: huge\n---\n\n# Added a feature\n",
+        vec![("Unknown bump type `huge`, expected `major`, `minor`, `patch` or `none`", Some(3))]
+    )]
+    #[case::invalid_pr(
+        "---\nchangeset/type: patch\nchangeset/pr: main\n---\n\n# Fixed a bug\n",
+        vec![("The pull request `main` isn't a number", Some(3))]
+    )]
+    #[case::invalid_issue(
+        "---\nchangeset/type: patch\nchangeset/issues: #12, abc\n---\n\n# Fixed a bug\n",
+        vec![("The issue `abc` isn't a number", Some(3))]
+    )]
+    #[case::invalid_order(
+        "---\nchangeset/type: patch\nchangeset/order: first\n---\n\n# Fixed a bug\n",
+        vec![("The order `first` isn't a number", Some(3))]
+    )]
+    #[case::multiple_problems(
+        "---\nchangeset/type: huge\n---\n\n#\n",
+        vec![
+            ("Unknown bump type `huge`, expected `major`, `minor`, `patch` or `none`", Some(2)),
+            ("The summary is empty", Some(5)),
+        ]
+    )]
+    fn test_parse_changeset_reports_every_problem(
+        #[case] contents: &str,
+        #[case] expected: Vec<(&str, Option<usize>)>,
+    ) {
+        let path = PathBuf::from(".changeset/change.md");
+
+        let diagnostics = parse_changeset(&path, contents).unwrap_err();
+
+        let problems = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.line))
+            .collect::<Vec<_>>();
+        assert_eq!(problems, expected);
+        assert!(diagnostics.iter().all(|d| d.file == Some(path.clone())));
+    }
+
+    #[rstest]
+    #[case("README.md", true)]
+    #[case("nested/README.md", true)]
+    #[case("drafts/change.md", true)]
+    #[case("drafts", true)]
+    #[case("change.md", false)]
+    #[case("nested/change.md", false)]
+    fn test_is_ignored(#[case] relative_path: &str, #[case] expected: bool) {
+        let ignore = vec!["README.md".to_string(), "drafts/".to_string()];
+
+        assert_eq!(is_ignored(Path::new(relative_path), &ignore), expected);
+    }
+
+    #[rstest]
+    fn test_find_changeset_files_recurses_and_skips_ignored_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("core/nested")).unwrap();
+        std::fs::create_dir_all(root.join("archive/1.0.0")).unwrap();
+        for file in [
+            "README.md",
+            "first.md",
+            "notes.txt",
+            "core/second.toml",
+            "core/nested/third.json",
+            "archive/1.0.0/released.md",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            ..Default::default()
+        };
+
+        let files = find_changeset_files(&config).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                root.join("core/nested/third.json"),
+                root.join("core/second.toml"),
+                root.join("first.md"),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("fix", vec!["fix.md", "fix.toml"])]
+    #[case("fix.toml", vec!["fix.toml"])]
+    #[case("core/fix", vec!["core/fix.md"])]
+    #[case("core", vec![])]
+    #[case("missing", vec![])]
+    fn test_find_named_changeset_files(#[case] name: &str, #[case] expected: Vec<&str>) {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("core")).unwrap();
+        for file in ["fix.md", "fix.toml", "core/fix.md"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            ..Default::default()
+        };
+
+        let files = find_named_changeset_files(&config, name).unwrap();
+
+        let expected = expected.iter().map(|f| root.join(f)).collect::<Vec<_>>();
+        assert_eq!(files, expected);
+    }
+
+    #[rstest]
+    fn test_archive_moves_and_stamps_changesets() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("core")).unwrap();
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            archive: true,
+            ..Default::default()
+        };
+        let changesets = vec![
+            Change {
+                file_path: root.join("first.md"),
+                summary: "First".to_string(),
+                ..Default::default()
+            },
+            Change {
+                file_path: root.join("core").join("second.toml"),
+                summary: "Second".to_string(),
+                ..Default::default()
+            },
+        ];
+        changesets.iter().for_each(|c| c.save().unwrap());
+
+        changesets.archive(&config, "1.3.0", "1.2.3").unwrap();
+
+        assert!(!root.join("first.md").exists());
+        assert!(!root.join("core").join("second.toml").exists());
+        let archived = read_changeset(&root.join("archive/1.3.0/core/second.toml"))
+            .unwrap()
+            .remove(0);
+        assert_eq!(archived.summary, "Second");
+        assert_eq!(archived.version.as_deref(), Some("1.3.0"));
+        assert_eq!(archived.previous_version.as_deref(), Some("1.2.3"));
+        let archived = read_changeset(&root.join("archive/1.3.0/first.md"))
+            .unwrap()
+            .remove(0);
+        assert_eq!(archived.version.as_deref(), Some("1.3.0"));
+    }
+
+    #[rstest]
+    fn test_archive_conflict_moves_nothing() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("archive/1.3.0")).unwrap();
+        std::fs::write(root.join("archive/1.3.0/second.md"), "").unwrap();
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            archive: true,
+            ..Default::default()
+        };
+        let changesets = ["first.md", "second.md"]
+            .map(|file_name| Change {
+                file_path: root.join(file_name),
+                summary: file_name.to_string(),
+                ..Default::default()
+            })
+            .to_vec();
+        changesets.iter().for_each(|c| c.save().unwrap());
+
+        let result = changesets.archive(&config, "1.3.0", "1.2.3");
+
+        assert!(result.is_err());
+        assert!(root.join("first.md").exists());
+        assert!(root.join("second.md").exists());
+        assert!(!root.join("archive/1.3.0/first.md").exists());
+    }
+
+    #[rstest]
+    #[case("../1.3.0")]
+    #[case("release/1.3.0")]
+    fn test_archive_rejects_versions_outside_the_archive(#[case] version: &str) {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().join(".changeset");
+        let config = ChangesetsConfig {
+            directory: root.clone(),
+            archive: true,
+            ..Default::default()
+        };
+        let changesets = vec![Change {
+            file_path: root.join("first.md"),
+            summary: "First".to_string(),
+            ..Default::default()
+        }];
+        std::fs::create_dir_all(&root).unwrap();
+        changesets[0].save().unwrap();
+
+        let result = changesets.clone().archive(&config, version, "1.2.3");
+
+        assert!(result.is_err());
+        assert!(root.join("first.md").exists());
+        assert!(changesets.restore(&config, version).is_err());
+        assert!(get_archived_changesets(&config, version).is_err());
+    }
+
+    #[rstest]
+    #[case::markdown("multiple.md")]
+    #[case::toml("multiple.toml")]
+    #[case::json("multiple.json")]
+    fn test_archive_keeps_changes_sharing_a_file_together(#[case] file_name: &str) {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            archive: true,
+            ..Default::default()
+        };
+        let changesets = ["First", "Second"]
+            .map(|summary| Change {
+                file_path: root.join(file_name),
+                summary: summary.to_string(),
+                ..Default::default()
+            })
+            .to_vec();
+        std::fs::write(
+            root.join(file_name),
+            render_changeset_file(&changesets).unwrap(),
+        )
+        .unwrap();
+
+        changesets.archive(&config, "1.3.0", "1.2.3").unwrap();
+
+        assert!(!root.join(file_name).exists());
+        let archived = read_changeset(&root.join("archive/1.3.0").join(file_name)).unwrap();
+        let archived = archived
+            .iter()
+            .map(|c| (c.summary.as_str(), c.version.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            archived,
+            vec![("First", Some("1.3.0")), ("Second", Some("1.3.0"))]
+        );
+    }
+
+    #[rstest]
+    fn test_consume_removes_files_containing_several_changes_once() {
+        let directory = tempfile::tempdir().unwrap();
+        let file_path = directory.path().join("multiple.md");
+        std::fs::write(&file_path, "").unwrap();
+        let changesets = ["First", "Second"]
+            .map(|summary| Change {
+                file_path: file_path.clone(),
+                summary: summary.to_string(),
+                ..Default::default()
+            })
+            .to_vec();
+
+        changesets.consume().unwrap();
+
+        assert!(!file_path.exists());
+    }
+
+    #[rstest]
+    fn test_restore_reverses_archive() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        std::fs::create_dir_all(root.join("core")).unwrap();
+        let config = ChangesetsConfig {
+            directory: root.to_path_buf(),
+            archive: true,
+            ..Default::default()
+        };
+        let changesets = [
+            Change {
+                file_path: root.join("first.md"),
+                summary: "First".to_string(),
+                ..Default::default()
+            },
+            Change {
+                file_path: root.join("core").join("second.md"),
+                summary: "Second".to_string(),
+                ..Default::default()
+            },
+        ];
+        changesets.iter().for_each(|c| c.save().unwrap());
+        let original = get_changesets(&config).unwrap();
+        original.clone().archive(&config, "1.3.0", "1.2.3").unwrap();
+
+        assert_eq!(
+            find_release_after(&config, "1.2.3").unwrap().as_deref(),
+            Some("1.3.0")
+        );
+        assert_eq!(find_release_after(&config, "1.3.0").unwrap(), None);
+
+        let archived = get_archived_changesets(&config, "1.3.0").unwrap();
+        archived.restore(&config, "1.3.0").unwrap();
+
+        let restored = get_changesets(&config).unwrap();
+        let contents = |changesets: &[Change]| {
+            changesets
+                .iter()
+                .map(|c| (c.file_path.clone(), c.to_contents().unwrap()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contents(&restored), contents(&original));
+        assert!(!root.join(ARCHIVE_DIRECTORY).join("1.3.0").exists());
+        assert!(get_archived_changesets(&config, "1.3.0").is_err());
+    }
+
+    #[rstest]
+    #[case("Added a new feature", "added-a-new-feature")]
+    #[case("Fix: `get_version` panics!", "fix-get-version-panics")]
+    #[case("feature/JIRA-123_add-thing", "feature-jira-123-add-thing")]
+    #[case("   ", "")]
+    #[case(
+        "A very long message which goes on and on and on and on and on",
+        "a-very-long-message-which-goes-on-and-on-and-on-an"
+    )]
+    fn test_slugify(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(slugify(input), expected);
+    }
+
+    #[rstest]
+    fn test_create_unique_file_retries_when_name_exists() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let (first, _) = create_unique_file(
+            directory.path(),
+            &NamingStrategy::Slug,
+            "Added a feature",
+            ChangesetFormat::Markdown,
+        )
+        .unwrap();
+        let (second, _) = create_unique_file(
+            directory.path(),
+            &NamingStrategy::Slug,
+            "Added a feature",
+            ChangesetFormat::Markdown,
+        )
+        .unwrap();
+
+        assert_eq!(first, directory.path().join("added-a-feature.md"));
+        assert_eq!(second, directory.path().join("added-a-feature-2.md"));
+    }
+
+    #[rstest]
+    fn test_create_unique_file_falls_back_to_words_for_empty_slug() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let (path, _) = create_unique_file(
+            directory.path(),
+            &NamingStrategy::Slug,
+            "",
+            ChangesetFormat::Markdown,
+        )
+        .unwrap();
+
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        assert_eq!(name.split('-').count(), CHANGE_NAME_PARTS as usize);
+    }
+
+    #[rstest]
+    #[case::summary_only(Change {
+        bump_type: IncrementType::Minor,
+        summary: "Added a feature".to_string(),
+        ..Default::default()
+    }, "---\nchangeset/type: minor\n---\n\n# Added a feature\n")]
+    #[case::with_description_and_package(Change {
+        bump_type: IncrementType::Major,
+        summary: "Removed a feature".to_string(),
+        description: "Use the other feature instead.".to_string(),
+        package: Some("core".to_string()),
+        ..Default::default()
+    }, "---\nchangeset/type: major\nchangeset/package: core\n---\n\n# Removed a feature\n\nUse the other feature instead.\n")]
+    #[case::with_attribution(Change {
+        bump_type: IncrementType::Patch,
+        summary: "Fixed a bug".to_string(),
+        authors: vec!["alice".to_string(), "bob".to_string()],
+        pr: Some(123),
+        issues: vec![45],
+        order: Some(2),
+        ..Default::default()
+    }, "---\nchangeset/type: patch\nchangeset/authors: alice, bob\nchangeset/pr: 123\nchangeset/issues: 45\nchangeset/order: 2\n---\n\n# Fixed a bug\n")]
+    fn test_change_to_markdown(#[case] change: Change, #[case] expected: &str) {
+        assert_eq!(change.to_markdown(), expected);
+    }
+
+    #[rstest]
+    #[case::markdown("change.md")]
+    #[case::toml("change.toml")]
+    #[case::json("change.json")]
+    fn test_change_save_round_trips(#[case] file_name: &str) {
+        let directory = tempfile::tempdir().unwrap();
+        let change = Change {
+            file_path: directory.path().join(file_name),
+            bump_type: IncrementType::Patch,
+            summary: "Fixed a bug".to_string(),
+            description: "It no longer crashes.\n\n## Details\n\nIt was crashing.".to_string(),
+            package: Some("cli".to_string()),
+            authors: vec!["alice".to_string(), "bob".to_string()],
+            pr: Some(123),
+            issues: vec![45, 67],
+            order: Some(-3),
+            ..Default::default()
+        };
+
+        change.save().unwrap();
+        let loaded = read_changeset(&change.file_path).unwrap().remove(0);
+
+        assert_eq!(loaded.to_contents().unwrap(), change.to_contents().unwrap());
+        assert_eq!(loaded.description, change.description);
+    }
 }
@@ -1,17 +1,29 @@
 use crate::changelog;
-use crate::changeset::{self, Bump, ChangeSetExt, IncrementType};
-use crate::plugin::{self, set_version_via_plugin};
+use crate::changeset::{self, ChangeSetExt, IncrementType};
+use crate::config::{ChangeSource, ChangesetsConfig, Config, get_config, get_config_if_exists};
+use crate::conventional;
+use crate::editor;
+use crate::git;
+use crate::lint;
+use crate::plugin::{self, set_version_via_plugin, validate_changesets_via_plugin};
+use crate::template;
+use crate::utils;
+use crate::versioning;
 use clap::{Args, Parser, Subcommand, ValueEnum};
-use cliclack::{input, select};
-use semver::Version;
+use cliclack::{confirm, input, multiselect, select};
+use serde::Serialize;
 use std::io::{Read, Write};
-use std::{fs::File, path::PathBuf};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser, Debug, PartialEq, Clone, ValueEnum, Eq)]
 pub enum BumpType {
     Major,
     Minor,
     Patch,
+    None,
 }
 
 impl BumpType {
@@ -20,10 +32,61 @@ impl BumpType {
             BumpType::Major => IncrementType::Major,
             BumpType::Minor => IncrementType::Minor,
             BumpType::Patch => IncrementType::Patch,
+            BumpType::None => IncrementType::None,
         }
     }
 }
 
+impl From<&IncrementType> for BumpType {
+    fn from(increment_type: &IncrementType) -> Self {
+        match increment_type {
+            IncrementType::Major => BumpType::Major,
+            IncrementType::Minor => BumpType::Minor,
+            IncrementType::Patch => BumpType::Patch,
+            IncrementType::None => BumpType::None,
+        }
+    }
+}
+
+fn select_bump_type(initial_value: Option<BumpType>) -> anyhow::Result<BumpType> {
+    let mut prompt = select("Which type of increment?").items(&[
+        (BumpType::Major, "Major", ""),
+        (BumpType::Minor, "Minor", ""),
+        (BumpType::Patch, "Patch", ""),
+        (
+            BumpType::None,
+            "None",
+            "Internal change, doesn't release a new version",
+        ),
+    ]);
+    if let Some(initial_value) = initial_value {
+        prompt = prompt.initial_value(initial_value);
+    }
+
+    Ok(prompt.interact()?)
+}
+
+/// Prompts for one of the templates, returning its contents. `None` when there aren't any
+/// templates or none was chosen
+fn select_template(config: &ChangesetsConfig) -> anyhow::Result<Option<String>> {
+    let templates = template::list_templates(config)?;
+    if templates.is_empty() {
+        return Ok(None);
+    }
+
+    let mut items = vec![(None, "None".to_string(), "")];
+    items.extend(
+        templates
+            .into_iter()
+            .map(|name| (Some(name.clone()), name, "")),
+    );
+    let selected: Option<String> = select("Which template?").items(&items).interact()?;
+
+    selected
+        .map(|name| template::load_template(config, &name))
+        .transpose()
+}
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
@@ -39,14 +102,119 @@ pub struct AddCommand {
 
     #[arg(short = 'm', long = "message")]
     pub message: Option<String>,
+
+    /// The description of the change, written below the summary
+    #[arg(short = 'd', long = "description", conflicts_with = "description_file")]
+    pub description: Option<String>,
+
+    /// Reads the description from a file, or from stdin when `-`
+    #[arg(long = "description-file")]
+    pub description_file: Option<PathBuf>,
+
+    /// Fills the description from `<directory>/templates/<name>.md`
+    #[arg(
+        long = "template",
+        conflicts_with_all = ["description", "description_file"]
+    )]
+    pub template: Option<String>,
+
+    /// The name of the changeset file, without its extension. Fails if it's already taken
+    #[arg(short = 'n', long = "name")]
+    pub name: Option<String>,
+
+    /// The package the change applies to, within a monorepo
+    #[arg(short = 'p', long = "package")]
+    pub package: Option<String>,
+
+    /// Open `$VISUAL`/`$EDITOR` to write the changeset, the message is optional when editing
+    #[arg(short = 'e', long = "edit")]
+    pub edit: bool,
+
+    /// Fails instead of prompting for missing input, for use within scripts and CI
+    #[arg(long = "no-interactive", conflicts_with = "edit")]
+    pub no_interactive: bool,
+
+    /// Outputs the created changeset as JSON
+    #[arg(long = "json", conflicts_with = "edit")]
+    pub json: bool,
+
+    /// The file format to write the changeset in
+    #[arg(short = 'f', long = "format", value_enum, default_value_t)]
+    pub format: changeset::ChangesetFormat,
 }
 
 #[derive(Debug, Args)]
-pub struct VersionCommand {}
+pub struct FromCommitsCommand {
+    /// The git reference to generate changesets since, e.g. the latest release's tag
+    #[arg(long = "since")]
+    pub since: String,
+
+    /// Creates a single changeset for each scope rather than for each commit
+    #[arg(long = "per-scope")]
+    pub per_scope: bool,
+
+    /// The file format to write the changesets in
+    #[arg(short = 'f', long = "format", value_enum, default_value_t)]
+    pub format: changeset::ChangesetFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct VersionCommand {
+    /// Reverts the latest release, restoring its archived changesets and the previous version
+    #[arg(long = "revert")]
+    pub revert: bool,
+}
 
 #[derive(Debug, Args)]
 pub struct GetCommand {}
 
+#[derive(Debug, Args)]
+pub struct LintCommand {}
+
+#[derive(Debug, Args)]
+pub struct ListCommand {
+    /// Only list changesets of this type
+    #[arg(short = 't', long = "type")]
+    pub increment_type: Option<BumpType>,
+
+    /// Only list changesets for this package
+    #[arg(short = 'p', long = "package")]
+    pub package: Option<String>,
+
+    /// Output the changesets as JSON
+    #[arg(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct EditCommand {
+    /// The name of the changeset, i.e. its path within the changeset directory, with or without
+    /// the extension
+    pub name: String,
+
+    /// The new type of increment
+    #[arg(short = 't', long = "type")]
+    pub increment_type: Option<BumpType>,
+
+    /// The new summary message
+    #[arg(short = 'm', long = "message")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RemoveCommand {
+    /// The names of the changesets to remove, i.e. their paths within the changeset directory. When
+    /// omitted, the changesets are selected interactively
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowCommand {
+    /// The name of the changeset, i.e. its path within the changeset directory, with or without
+    /// the extension
+    pub name: String,
+}
+
 #[derive(Parser)]
 pub enum PreviewCommands {
     Version(GetCommand),
@@ -64,92 +232,707 @@ pub enum Commands {
     Version(VersionCommand),
     Get(GetCommand),
     Preview(Preview),
+    /// Checks the changesets are valid, exiting with a non-zero status code if they aren't
+    Lint(LintCommand),
+    /// Lists the pending changesets
+    List(ListCommand),
+    /// Shows the summary and description of a changeset
+    Show(ShowCommand),
+    /// Changes the type or summary of a changeset, preserving its description
+    Edit(EditCommand),
+    /// Removes changesets
+    Remove(RemoveCommand),
+    /// Generates changesets from the Conventional Commits made since a git reference
+    FromCommits(FromCommitsCommand),
 }
 
-pub fn add_changeset(command: &AddCommand) {
-    let increment_type = command.increment_type.as_ref().unwrap_or_else(|| {
-        select("Which type of increment?")
-            .items(&[
-                (&BumpType::Major, "Major", ""),
-                (&BumpType::Minor, "Minor", ""),
-                (&BumpType::Patch, "Patch", ""),
-            ])
-            .interact()
-            .unwrap()
-    });
+/// The changesets config, using the defaults when the project hasn't been configured
+fn get_changesets_config() -> anyhow::Result<ChangesetsConfig> {
+    Ok(get_config_if_exists()?
+        .map(|config| config.changesets)
+        .unwrap_or_default())
+}
 
-    let message = command.message.clone().unwrap_or_else(|| {
-        input("Enter a message for the changeset:")
-            .interact()
-            .unwrap()
-    });
+pub fn add_changeset(command: &AddCommand) -> anyhow::Result<()> {
+    let (add_config, changesets_config) = match get_config_if_exists()? {
+        Some(config) => (config.add, config.changesets),
+        None => Default::default(),
+    };
+    // Scripts can't use the editor, even when it's enabled within the config
+    let edit = !command.no_interactive && (command.edit || add_config.edit);
+
+    let increment_type = match &command.increment_type {
+        Some(increment_type) => increment_type.clone(),
+        None if command.no_interactive => {
+            return Err(anyhow::anyhow!(
+                "The type of increment is required, pass it with --type"
+            ));
+        }
+        None => select_bump_type(None)?,
+    };
+
+    // Templates are only offered within the interactive flow, so scripts aren't interrupted
+    let template = match &command.template {
+        Some(name) => Some(template::load_template(&changesets_config, name)?),
+        None if command.increment_type.is_none()
+            && !command.no_interactive
+            && command.description.is_none()
+            && command.description_file.is_none() =>
+        {
+            select_template(&changesets_config)?
+        }
+        None => None,
+    };
+
+    // The summary can be written within the editor instead
+    let message = match (&command.message, edit) {
+        (Some(message), _) => message.clone(),
+        (None, true) => String::new(),
+        (None, false) if command.no_interactive => {
+            return Err(anyhow::anyhow!(
+                "The message is required, pass it with --message"
+            ));
+        }
+        (None, false) => input("Enter a message for the changeset:").interact()?,
+    };
+
+    let description = match (&command.description, &command.description_file) {
+        (Some(description), _) => description.clone(),
+        (None, Some(path)) if path == Path::new("-") => std::io::read_to_string(std::io::stdin())?,
+        (None, Some(path)) => std::fs::read_to_string(path).map_err(|e| {
+            anyhow::anyhow!(
+                "Unable to read the description from {}: {e}",
+                path.display()
+            )
+        })?,
+        (None, None) => String::new(),
+    };
+
+    let mut change = changeset::Change {
+        bump_type: increment_type.to_increment_type(),
+        summary: message,
+        description: description.trim().to_string(),
+        package: command.package.clone(),
+        ..Default::default()
+    };
+    change.attribute_to_current_user();
+    if let Some(template) = template {
+        let values = template::TemplateValues {
+            // Left as a placeholder when the summary is yet to be written within the editor, and
+            // filled in once it has been
+            summary: match change.summary.is_empty() {
+                true => template::SUMMARY_PLACEHOLDER.to_string(),
+                false => change.summary.clone(),
+            },
+            author: [change.authors.clone(), change.git_authors.clone()]
+                .concat()
+                .join(", "),
+            date: chrono::Utc::now().format("%d-%m-%Y").to_string(),
+        };
+        change.description = template::render_template(&template, &values)
+            .trim()
+            .to_string();
+    }
+    let change_file = match &command.name {
+        Some(name) => changeset::create_named_change_file(
+            change,
+            name,
+            command.format,
+            &changesets_config.directory,
+        )?,
+        None => changeset::create_change_file(
+            change,
+            &add_config.naming,
+            command.format,
+            &changesets_config.directory,
+        )?,
+    };
+
+    if edit {
+        return edit_change_file(&change_file);
+    }
+
+    if command.json {
+        let output = serde_json::json!({ "path": change_file });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-    let change_file =
-        changeset::create_change_file(increment_type.to_increment_type(), message.as_str())
-            .unwrap();
     println!("Changeset created at: {}", change_file.display());
+    return Ok(());
 }
 
-pub fn get_version() -> Version {
-    let ver = plugin::get_version_via_plugin();
-    match ver {
-        Ok(v) => {
-            println!("{}", v);
-            return v;
+/// Opens the changeset within the user's editor until it's valid. The changeset is removed if
+/// the user empties the file, or if it can't be made valid, so it doesn't break releases
+fn edit_change_file(change_file: &Path) -> anyhow::Result<()> {
+    let result = edit_until_valid(change_file);
+    if result.is_err() && change_file.exists() {
+        std::fs::remove_file(change_file)?;
+    }
+
+    result
+}
+
+fn edit_until_valid(change_file: &Path) -> anyhow::Result<()> {
+    loop {
+        editor::open_in_editor(change_file)?;
+
+        let contents = std::fs::read_to_string(change_file)?;
+        if contents.trim().is_empty() {
+            std::fs::remove_file(change_file)?;
+            println!("The changeset was empty so it has been removed");
+            return Ok(());
         }
-        Err(e) => {
-            println!("Error: {}", e);
-            return Version::new(0, 0, 0);
+
+        match changeset::read_changeset(change_file) {
+            Ok(mut changes) => {
+                if contents.contains(template::SUMMARY_PLACEHOLDER) {
+                    for change in &mut changes {
+                        change.description = change
+                            .description
+                            .replace(template::SUMMARY_PLACEHOLDER, &change.summary);
+                    }
+                    std::fs::write(change_file, changeset::render_changeset_file(&changes)?)?;
+                }
+                println!("Changeset created at: {}", change_file.display());
+                return Ok(());
+            }
+            Err(diagnostics) => {
+                let e = diagnostics
+                    .iter()
+                    .map(|d| d.message.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let reopen = confirm(format!("The changeset is invalid: {e}. Edit it again?"))
+                    .initial_value(true)
+                    .interact()?;
+                if !reopen {
+                    return Err(anyhow::anyhow!(
+                        "The changeset at {} is invalid: {e}",
+                        change_file.display()
+                    ));
+                }
+            }
         }
     }
 }
 
-pub fn preview_version_command() {
-    let current_version = plugin::get_version_via_plugin().unwrap();
-    let changesets = changeset::get_changesets().unwrap();
-    let bump_type = changesets.determine_final_bump_type().unwrap();
-    let new_version = bump_type.map(|bump_type| current_version.bump(&bump_type));
-    if new_version.is_none() {
-        println!("There aren't any changes!");
-        return;
-    }
+pub fn get_version() -> anyhow::Result<String> {
+    let config = get_config()?;
+    let mut plugin = plugin::setup_plugin(&config, true)?;
+    let version = plugin::get_version_via_plugin(&mut plugin)?;
+    println!("{}", version);
+    return Ok(version);
+}
+
+/// The changes to release, from either the changeset files or the commits since the latest
+/// release depending on the config
+fn get_pending_changes(config: &Config) -> anyhow::Result<Vec<changeset::Change>> {
+    let mut changes = match config.changesets.source {
+        ChangeSource::Files => {
+            let mut changesets = changeset::get_changesets(&config.changesets)?;
+            changesets.attach_commits();
+            changesets
+        }
+        ChangeSource::Commits => conventional::changes_since_latest_release(
+            Path::new("."),
+            &config.changesets.release_tags,
+        )?,
+    };
+    changes.sort_changesets(config.changelog.sort);
+
+    Ok(changes)
+}
 
+pub fn preview_version_command() -> anyhow::Result<()> {
+    let config = get_config()?;
+    let mut plugin = plugin::setup_plugin(&config, true)?;
+    let current_version = plugin::get_version_via_plugin(&mut plugin)?;
+    let changesets = get_pending_changes(&config)?;
+    let bump_type = changesets.determine_final_bump_type()?;
+    let Some(bump_type) = bump_type else {
+        println!("There aren't any changes!");
+        return Ok(());
+    };
     let publish_date = chrono::Utc::now();
+    let new_version = versioning::bump_version(
+        &config.versioning,
+        &mut plugin,
+        &current_version,
+        &bump_type,
+        &publish_date,
+    )?;
 
-    let contents_to_insert =
-        changelog::generate_changelog_contents(&new_version.unwrap(), &changesets, publish_date);
+    let contents_to_insert = changelog::generate_changelog_contents(
+        &new_version,
+        &changesets,
+        publish_date,
+        &changelog_options(&config),
+    );
 
-    println!("{}", contents_to_insert)
+    println!("{}", contents_to_insert);
+    return Ok(());
 }
 
 pub fn version_command() -> anyhow::Result<()> {
-    let current_version = plugin::get_version_via_plugin()?;
-    let changesets = changeset::get_changesets()?;
+    // A single read-write instance is used for both reading and writing the version, so the
+    // plugin is only set up once
+    let config = get_config()?;
+    let mut plugin = plugin::setup_plugin(&config, false)?;
+    let current_version = plugin::get_version_via_plugin(&mut plugin)?;
+    let changesets = get_pending_changes(&config)?;
+    // The same checks as `lint`, so anything it reports also blocks the release
+    let mut diagnostics = find_duplicate_changesets(&config.changesets, &changesets);
+    diagnostics.extend(validate_changesets_via_plugin(
+        &mut plugin,
+        &current_version,
+        &changesets,
+    )?);
+    lint::report(&diagnostics)?;
+
+    let publish_date = chrono::Utc::now();
     let bump_type = changesets.determine_final_bump_type()?;
     let new_version = match bump_type {
         Some(bump_type) => {
-            let new_version = current_version.bump(&bump_type);
+            let new_version = versioning::bump_version(
+                &config.versioning,
+                &mut plugin,
+                &current_version,
+                &bump_type,
+                &publish_date,
+            )?;
             println!("Updating version from {current_version} to {new_version}");
             Some(new_version)
         }
-        None => {
+        None if changesets.is_empty() => {
             println!("No changesets found");
             None
         }
+        None => {
+            println!("Only internal changesets were found, a release isn't required");
+            None
+        }
     };
     if new_version.is_none() {
         return Ok(());
     }
     let new = new_version.unwrap();
-    set_version_via_plugin(&new)?;
+    set_version_via_plugin(&mut plugin, &new)?;
+
+    write_changelog(&changesets, &new, publish_date, &changelog_options(&config))?;
+    release_changesets(&config.changesets, changesets, &new, &current_version)?;
+    return Ok(());
+}
+
+/// The changesets which share a summary. Only changeset files are checked, as commits sharing a
+/// subject, e.g. two `fix: typo` commits, can't be changed without rewriting history
+fn find_duplicate_changesets(
+    config: &ChangesetsConfig,
+    changesets: &[changeset::Change],
+) -> Vec<lint::Diagnostic> {
+    match config.source {
+        ChangeSource::Files => lint::find_duplicate_summaries(changesets),
+        ChangeSource::Commits => vec![],
+    }
+}
 
-    write_changelog(&changesets, &new)?;
+/// Consumes or archives the released changesets
+fn release_changesets(
+    config: &ChangesetsConfig,
+    changesets: Vec<changeset::Change>,
+    version: &str,
+    previous_version: &str,
+) -> anyhow::Result<()> {
+    // Commits don't need consuming, the release's tag marks them as released
+    match config.source {
+        ChangeSource::Files if config.archive => {
+            changesets.archive(config, version, previous_version)
+        }
+        ChangeSource::Files => changesets.consume(),
+        ChangeSource::Commits => {
+            println!(
+                "Tag the release of {version} with a tag matching one of the `release_tags` patterns ({}), otherwise its commits will be released again",
+                config.release_tags.join(", ")
+            );
+            Ok(())
+        }
+    }
+}
+
+pub fn revert_version_command() -> anyhow::Result<()> {
+    let config = get_config()?;
+    let mut plugin = plugin::setup_plugin(&config, false)?;
+    let current_version = plugin::get_version_via_plugin(&mut plugin)?;
+
+    if let Some(newer) = changeset::find_release_after(&config.changesets, &current_version)? {
+        return Err(anyhow::anyhow!(
+            "Unable to revert {current_version} as a newer release, {newer}, has been archived"
+        ));
+    }
+
+    let changesets = changeset::get_archived_changesets(&config.changesets, &current_version)?;
+    let previous_version = changesets
+        .iter()
+        .find_map(|c| c.previous_version.clone())
+        .ok_or(anyhow::anyhow!(
+            "The archived changesets for {current_version} don't record the previous version"
+        ))?;
+
+    let existing_changelog = std::fs::read_to_string(changelog::CHANGELOG_FILENAME)?;
+    let reverted_changelog =
+        changelog::remove_latest_release(&existing_changelog, &current_version)?;
+
+    // The version and changelog are changed first as they're the simplest to roll back, should
+    // restoring the changesets fail
+    set_version_via_plugin(&mut plugin, &previous_version)?;
+    let restored = std::fs::write(changelog::CHANGELOG_FILENAME, &reverted_changelog)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| changesets.restore(&config.changesets, &current_version));
+    if let Err(e) = restored {
+        std::fs::write(changelog::CHANGELOG_FILENAME, &existing_changelog)?;
+        set_version_via_plugin(&mut plugin, &current_version)?;
+        return Err(e);
+    }
+
+    println!("Reverted the version from {current_version} to {previous_version}");
+    return Ok(());
+}
+
+pub fn lint_command() -> anyhow::Result<()> {
+    let config = get_config_if_exists()?;
+    let changesets_config = match &config {
+        Some(config) => &config.changesets,
+        None => &ChangesetsConfig::default(),
+    };
+    let (changesets, mut diagnostics) = changeset::load_changesets(changesets_config)?;
+    diagnostics.extend(lint::find_duplicate_summaries(&changesets));
+
+    // The plugin's validation is only performed once the project has been configured
+    if let Some(config) = &config {
+        let mut plugin = plugin::setup_plugin(config, true)?;
+        let current_version = plugin::get_version_via_plugin(&mut plugin)?;
+        diagnostics.extend(validate_changesets_via_plugin(
+            &mut plugin,
+            &current_version,
+            &changesets,
+        )?);
+    }
+
+    lint::report(&diagnostics)?;
+
+    println!("Found {} valid changeset(s)", changesets.len());
+    return Ok(());
+}
+
+#[derive(Debug, Serialize)]
+struct ListEntry {
+    name: String,
+    #[serde(flatten)]
+    change: changeset::Change,
+    author: Option<String>,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ListEntry {
+    fn new(change: changeset::Change, directory: &Path) -> Self {
+        // Falls back to when the file was last modified for changesets which haven't been committed
+        let (author, created_at) = match change.introduced_in() {
+            Some(commit) => (Some(commit.author.clone()), Some(commit.date)),
+            None => {
+                let modified = std::fs::metadata(&change.file_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(chrono::DateTime::<chrono::Utc>::from);
+                (None, modified)
+            }
+        };
+
+        ListEntry {
+            name: change.relative_name(directory),
+            change,
+            author,
+            created_at,
+        }
+    }
+}
+
+pub fn list_command(command: &ListCommand) -> anyhow::Result<()> {
+    let increment_type = command
+        .increment_type
+        .as_ref()
+        .map(|t| t.to_increment_type());
+    let config = get_changesets_config()?;
+    let mut changesets = changeset::get_changesets(&config)?;
+    changesets.attach_commits();
+    let entries = changesets
+        .into_iter()
+        .filter(|c| increment_type.as_ref().is_none_or(|t| &c.bump_type == t))
+        .filter(|c| {
+            command
+                .package
+                .as_ref()
+                .is_none_or(|package| c.package.as_ref() == Some(package))
+        })
+        .map(|change| ListEntry::new(change, &config.directory))
+        .collect::<Vec<_>>();
+
+    if command.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("There aren't any changesets");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    let header = ["NAME", "TYPE", "SUMMARY", "AUTHOR", "AGE"].map(String::from);
+    let rows = entries.iter().map(|entry| {
+        vec![
+            entry.name.clone(),
+            entry.change.bump_type.to_string(),
+            entry.change.summary.clone(),
+            entry.author.clone().unwrap_or("-".to_string()),
+            entry
+                .created_at
+                .map(|created_at| utils::format_age(now - created_at))
+                .unwrap_or("-".to_string()),
+        ]
+    });
+
+    let table = std::iter::once(header.to_vec())
+        .chain(rows)
+        .collect::<Vec<_>>();
+    println!("{}", utils::format_table(&table));
+    return Ok(());
+}
+
+pub fn show_command(command: &ShowCommand) -> anyhow::Result<()> {
+    let changes = find_changes(&command.name)?;
+
+    // Files containing several changes show each of them in turn
+    for (index, change) in changes.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        show_change(change);
+    }
+    return Ok(());
+}
+
+fn show_change(change: &changeset::Change) {
+    println!("{} ({})", change.summary, change.bump_type);
+    if let Some(package) = &change.package {
+        println!("Package: {package}");
+    }
+    let authors = [change.authors.clone(), change.git_authors.clone()].concat();
+    if !authors.is_empty() {
+        println!("Authors: {}", authors.join(", "));
+    }
+    if let Some(pr) = change.pr {
+        println!("Pull request: #{pr}");
+    }
+    if !change.issues.is_empty() {
+        let issues = change
+            .issues
+            .iter()
+            .map(|i| format!("#{i}"))
+            .collect::<Vec<_>>();
+        println!("Issues: {}", issues.join(", "));
+    }
+    if !change.description.is_empty() {
+        println!("\n{}", change.description);
+    }
+}
+
+/// The file of the changeset named `name`, failing if there isn't one or the name refers to
+/// several files, e.g. both `fix.md` and `fix.toml`
+fn find_changeset_file(config: &ChangesetsConfig, name: &str) -> anyhow::Result<PathBuf> {
+    let mut files = changeset::find_named_changeset_files(config, name)?;
+    match files.len() {
+        0 => Err(anyhow::anyhow!("No changeset named `{name}` was found")),
+        1 => Ok(files.remove(0)),
+        _ => {
+            let candidates = files
+                .iter()
+                .map(|file| {
+                    let relative_path = file.strip_prefix(&config.directory).unwrap_or(file);
+                    relative_path.display().to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(anyhow::anyhow!(
+                "The name `{name}` refers to several changesets, pass one of: {candidates}"
+            ))
+        }
+    }
+}
+
+/// The changes within the changeset named `name`. Only that changeset has to be valid, so others
+/// being invalid doesn't get in the way
+fn find_changes(name: &str) -> anyhow::Result<Vec<changeset::Change>> {
+    let file = find_changeset_file(&get_changesets_config()?, name)?;
+    changeset::read_changeset(&file).map_err(|diagnostics| {
+        let problems = diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        anyhow::anyhow!("The changeset `{name}` is invalid\n{problems}")
+    })
+}
+
+pub fn edit_command(command: &EditCommand) -> anyhow::Result<()> {
+    let mut changes = find_changes(&command.name)?;
+    if changes.len() > 1 {
+        return Err(anyhow::anyhow!(
+            "The changeset `{}` contains {} changes, edit its file directly instead",
+            command.name,
+            changes.len()
+        ));
+    }
+    let mut change = changes.remove(0);
+
+    // Prompts for both when neither are provided, using the existing values as the defaults
+    let interactive = command.increment_type.is_none() && command.message.is_none();
+    let increment_type = match &command.increment_type {
+        Some(increment_type) => Some(increment_type.clone()),
+        None if interactive => Some(select_bump_type(Some(BumpType::from(&change.bump_type)))?),
+        None => None,
+    };
+    let message = match &command.message {
+        Some(message) => Some(message.clone()),
+        None if interactive => Some(
+            input("Enter a message for the changeset:")
+                .default_input(&change.summary)
+                .interact()?,
+        ),
+        None => None,
+    };
+
+    if let Some(increment_type) = increment_type {
+        change.bump_type = increment_type.to_increment_type();
+    }
+    if let Some(message) = message {
+        change.summary = message;
+    }
+    change.save()?;
+
+    println!("Changeset updated at: {}", change.file_path.display());
+    return Ok(());
+}
+
+pub fn remove_command(command: &RemoveCommand) -> anyhow::Result<()> {
+    let config = get_changesets_config()?;
+    let files = if command.names.is_empty() {
+        // Invalid changesets can't be listed, but can still be removed by name
+        let (changesets, _) = changeset::load_changesets(&config)?;
+        if changesets.is_empty() {
+            println!("There aren't any changesets");
+            return Ok(());
+        }
+
+        let mut items: Vec<(PathBuf, String, String)> = vec![];
+        for change in &changesets {
+            // Files containing several changes are listed once, alongside every summary
+            match items
+                .iter_mut()
+                .find(|(path, _, _)| *path == change.file_path)
+            {
+                Some((_, _, summaries)) => summaries.push_str(&format!(", {}", change.summary)),
+                None => {
+                    let relative_path = change
+                        .file_path
+                        .strip_prefix(&config.directory)
+                        .unwrap_or(&change.file_path);
+                    items.push((
+                        change.file_path.clone(),
+                        relative_path.display().to_string(),
+                        change.summary.clone(),
+                    ));
+                }
+            }
+        }
+        multiselect("Which changesets should be removed?")
+            .items(&items)
+            .required(false)
+            .interact()?
+    } else {
+        // Every name is checked before anything is removed
+        command
+            .names
+            .iter()
+            .map(|name| find_changeset_file(&config, name))
+            .collect::<anyhow::Result<Vec<_>>>()?
+    };
 
-    changesets.consume(&current_version)?;
+    let mut removed: Vec<&Path> = vec![];
+    for file in &files {
+        if removed.contains(&file.as_path()) {
+            continue;
+        }
+        std::fs::remove_file(file)?;
+        println!("Removed {}", file.display());
+        removed.push(file);
+    }
     return Ok(());
 }
 
-pub fn write_changelog(changesets: &[changeset::Change], new: &Version) -> anyhow::Result<()> {
+fn changelog_options(config: &Config) -> changelog::ChangelogOptions {
+    changelog::ChangelogOptions {
+        repository_url: git::repository_url(),
+        include_internal: config.changelog.internal,
+    }
+}
+
+pub fn from_commits_command(command: &FromCommitsCommand) -> anyhow::Result<()> {
+    let (add_config, changesets_config) = match get_config_if_exists()? {
+        Some(config) => (config.add, config.changesets),
+        None => Default::default(),
+    };
+    let mut summaries = match changesets_config.directory.exists() {
+        true => changeset::get_changesets(&changesets_config)?
+            .into_iter()
+            .map(|c| c.summary.to_lowercase())
+            .collect::<Vec<_>>(),
+        false => vec![],
+    };
+
+    let entries = git::log_since(Path::new("."), Some(&command.since))?;
+    let changes = conventional::changes_from_commits(&entries, command.per_scope);
+
+    let mut skipped = 0;
+    for change in changes {
+        // Skips changes which already have a changeset, e.g. when run more than once
+        let summary = change.summary.to_lowercase();
+        if summaries.contains(&summary) {
+            skipped += 1;
+            continue;
+        }
+        summaries.push(summary);
+
+        let change_file = changeset::create_change_file(
+            change,
+            &add_config.naming,
+            command.format,
+            &changesets_config.directory,
+        )?;
+        println!("Changeset created at: {}", change_file.display());
+    }
+
+    if skipped > 0 {
+        println!("Skipped {skipped} change(s) which already have a changeset");
+    }
+    return Ok(());
+}
+
+pub fn write_changelog(
+    changesets: &[changeset::Change],
+    new: &str,
+    publish_date: chrono::DateTime<chrono::Utc>,
+    options: &changelog::ChangelogOptions,
+) -> anyhow::Result<()> {
     let existing_changelog_path = PathBuf::from(changelog::CHANGELOG_FILENAME);
 
     let mut existing_changelog = String::new();
@@ -168,10 +951,9 @@ pub fn write_changelog(changesets: &[changeset::Change], new: &Version) -> anyho
             .unwrap();
     }
 
-    let today = chrono::Utc::now();
-
     let new_contents =
-        changelog::generate_changelog(&existing_changelog, new, changesets, today).unwrap();
+        changelog::generate_changelog(&existing_changelog, new, changesets, publish_date, options)
+            .unwrap();
 
     let mut changelog_file = File::options()
         .create(true)
@@ -206,15 +988,65 @@ mod tests {
             description: "Description".to_string(),
             bump_type: IncrementType::Major,
             file_path: PathBuf::new(),
+            ..Default::default()
         }];
 
-        let new_version = Version::new(1, 0, 0);
+        let new_version = "1.0.0";
         // Assert no changelog file exists before running the func
         assert!(!Path::new(changelog::CHANGELOG_FILENAME).exists());
 
-        let result = write_changelog(&changesets, &new_version);
+        let result = write_changelog(
+            &changesets,
+            new_version,
+            chrono::Utc::now(),
+            &changelog::ChangelogOptions::default(),
+        );
         assert!(result.is_ok());
 
         assert!(Path::new(changelog::CHANGELOG_FILENAME).exists());
     }
+
+    #[rstest]
+    #[case(ChangeSource::Files, 1)]
+    #[case(ChangeSource::Commits, 0)]
+    fn test_find_duplicate_changesets(#[case] source: ChangeSource, #[case] expected: usize) {
+        let config = ChangesetsConfig {
+            source,
+            ..Default::default()
+        };
+        let typo = changeset::Change {
+            summary: "Typo".to_string(),
+            bump_type: IncrementType::Patch,
+            ..Default::default()
+        };
+        let changesets = vec![typo.clone(), typo];
+
+        let diagnostics = find_duplicate_changesets(&config, &changesets);
+
+        assert_eq!(diagnostics.len(), expected);
+    }
+
+    #[rstest]
+    #[case(ChangeSource::Files, false)]
+    #[case(ChangeSource::Commits, true)]
+    fn test_release_changesets(#[case] source: ChangeSource, #[case] kept: bool) {
+        let directory = tempdir().unwrap();
+        let file_path = directory.path().join("change.md");
+        std::fs::write(&file_path, "#### Summary\n").unwrap();
+        let config = ChangesetsConfig {
+            directory: directory.path().to_path_buf(),
+            source,
+            ..Default::default()
+        };
+        let changesets = vec![changeset::Change {
+            summary: "Summary".to_string(),
+            bump_type: IncrementType::Patch,
+            file_path: file_path.clone(),
+            ..Default::default()
+        }];
+
+        release_changesets(&config, changesets, "1.0.1", "1.0.0").unwrap();
+
+        assert_eq!(file_path.exists(), kept);
+    }
 }
@@ -1,3 +1,5 @@
+use crate::calver;
+use crate::signature::{SIGNATURE_EXTENSION, verify_signature};
 use home::home_dir;
 use serde::{Deserialize, Serialize};
 use sha256::Sha256Digest;
@@ -6,6 +8,8 @@ use std::path::PathBuf;
 
 pub const CHANGESET_DIRECTORY: &str = ".changeset";
 pub const CONFIG_FILENAME: &str = "config.json";
+const DEFAULT_IGNORE: [&str; 1] = ["README.md"];
+const DEFAULT_RELEASE_TAGS: [&str; 2] = ["v[0-9]*", "[0-9]*"];
 
 #[derive(Serialize, Deserialize)]
 pub struct Plugin {
@@ -14,6 +18,39 @@ pub struct Plugin {
     /// `https://github.com/owner/repo/releases/download/version/plugin.wasm`
     pub url: String,
     pub sha256: Option<String>,
+    /// Trusted minisign public keys. When set, the plugin must be accompanied by a detached
+    /// signature (`plugin.wasm.sig`) made by one of these keys
+    #[serde(default)]
+    pub public_keys: Vec<String>,
+    /// Restricts which files the plugin can access. When unset, the whole project is mounted
+    pub permissions: Option<Permissions>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Permissions {
+    /// Paths, relative to the project root, the plugin is allowed to read
+    #[serde(default)]
+    pub read: Vec<PathBuf>,
+    /// Paths, relative to the project root, the plugin is allowed to read and write
+    #[serde(default)]
+    pub write: Vec<PathBuf>,
+    /// Additional directories, outside the project root, to mount into the plugin
+    #[serde(default)]
+    pub mounts: Vec<Mount>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Mount {
+    /// The directory on the host to mount
+    pub host: PathBuf,
+    /// The path the directory is mounted at within the plugin
+    pub guest: String,
+    #[serde(default = "default_read_only")]
+    pub read_only: bool,
+}
+
+fn default_read_only() -> bool {
+    true
 }
 
 impl Plugin {
@@ -24,6 +61,20 @@ impl Plugin {
 
         return parse_shorthand_github_url(&self.url);
     }
+
+    pub fn get_signature_url(&self) -> anyhow::Result<String> {
+        let url = self.get_url()?;
+        Ok(format!("{url}.{SIGNATURE_EXTENSION}"))
+    }
+
+    fn verify_signature(&self, contents: &[u8], signature: Option<&str>) -> anyhow::Result<()> {
+        if self.public_keys.is_empty() {
+            return Ok(());
+        }
+
+        verify_signature(&self.public_keys, contents, signature)?;
+        Ok(())
+    }
 }
 
 /// Parses a shorthand github url and returns the full url. Example:
@@ -51,28 +102,184 @@ fn parse_shorthand_github_url(url: &str) -> anyhow::Result<String> {
     Ok(url)
 }
 
+/// How the next version is determined from the current version and the changesets
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+#[serde(tag = "scheme", rename_all = "lowercase")]
+pub enum VersionScheme {
+    /// The version is parsed and bumped as a semantic version
+    #[default]
+    Semver,
+    /// The version is treated as an opaque string, the plugin's `bump_version` export determines
+    /// the next version
+    Plugin,
+    /// The version is a calendar version, any changeset results in a new release for the current
+    /// period
+    Calver {
+        /// The tokens making up the version, e.g. `YYYY.0M.MICRO`
+        #[serde(default = "default_calver_format")]
+        format: String,
+    },
+}
+
+fn default_calver_format() -> String {
+    calver::DEFAULT_FORMAT.to_string()
+}
+
+/// How changeset files are named
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingStrategy {
+    /// Random words, e.g. `dog-cat-owl`
+    #[default]
+    Words,
+    /// Random words prefixed with the creation time, e.g. `20240615120000-dog-cat-owl`
+    Timestamp,
+    /// Derived from the summary, e.g. `added-a-new-feature`
+    Slug,
+    /// Derived from the current git branch, e.g. `feature-login`
+    Branch,
+}
+
+/// Defaults for the `add` command
+#[derive(Serialize, Deserialize, Default)]
+pub struct AddConfig {
+    /// Whether to open `$VISUAL`/`$EDITOR` to write the changeset
+    #[serde(default)]
+    pub edit: bool,
+    #[serde(default)]
+    pub naming: NamingStrategy,
+}
+
+/// The order changes are listed in within the changelog
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// When the changeset was committed, uncommitted changesets are listed last
+    #[default]
+    Git,
+    /// When the changeset file was last modified
+    Mtime,
+    /// The name of the changeset file
+    Name,
+    /// The `order` field of the changeset, changesets without one are listed last
+    Order,
+}
+
+/// Options for the generated changelog
+#[derive(Serialize, Deserialize, Default)]
+pub struct ChangelogConfig {
+    /// Whether changesets which don't bump the version are listed within an "Internal" section
+    #[serde(default)]
+    pub internal: bool,
+    #[serde(default)]
+    pub sort: SortOrder,
+}
+
+/// Where the pending changes are read from
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeSource {
+    /// Changeset files within the changeset directory
+    #[default]
+    Files,
+    /// Conventional Commits made since the latest release tag
+    Commits,
+}
+
+/// Where changesets are stored, and which files within the directory aren't changesets
+#[derive(Serialize, Deserialize)]
+pub struct ChangesetsConfig {
+    #[serde(default = "default_changesets_directory")]
+    pub directory: PathBuf,
+    /// File names, or paths relative to the directory, which are skipped when loading changesets
+    #[serde(default = "default_ignore")]
+    pub ignore: Vec<String>,
+    /// Whether consumed changesets are moved into the archive rather than deleted
+    #[serde(default)]
+    pub archive: bool,
+    #[serde(default)]
+    pub source: ChangeSource,
+    /// Glob patterns matching the tags releases are made with. The latest matching tag marks the
+    /// last release when the source is [`ChangeSource::Commits`]
+    #[serde(default = "default_release_tags")]
+    pub release_tags: Vec<String>,
+}
+
+fn default_changesets_directory() -> PathBuf {
+    PathBuf::from(CHANGESET_DIRECTORY)
+}
+
+fn default_ignore() -> Vec<String> {
+    DEFAULT_IGNORE.map(String::from).to_vec()
+}
+
+fn default_release_tags() -> Vec<String> {
+    DEFAULT_RELEASE_TAGS.map(String::from).to_vec()
+}
+
+impl Default for ChangesetsConfig {
+    fn default() -> Self {
+        ChangesetsConfig {
+            directory: default_changesets_directory(),
+            ignore: default_ignore(),
+            archive: false,
+            source: ChangeSource::default(),
+            release_tags: default_release_tags(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub plugin: Plugin,
+    #[serde(default)]
+    pub versioning: VersionScheme,
+    #[serde(default)]
+    pub add: AddConfig,
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+    #[serde(default)]
+    pub changesets: ChangesetsConfig,
+}
+
+/// The directory downloaded plugins, and their compiled modules, are cached within
+pub fn get_cache_dir() -> anyhow::Result<PathBuf> {
+    let home_dir = home_dir().ok_or(anyhow::anyhow!("no home dir"))?;
+    Ok(home_dir.join(".cache").join("changesets"))
 }
 
 impl Config {
     pub fn cache_plugin_from_url(&self) -> anyhow::Result<PathBuf> {
-        let home_dir = home_dir().ok_or(anyhow::anyhow!("no home dir"))?;
-        let cache_dir = home_dir.join(".cache").join("changesets");
+        let cache_dir = get_cache_dir()?;
 
         let plugin_url = self.plugin.get_url()?;
         let plugin_url_hash = sha256::digest(&plugin_url);
         let plugin_dir = cache_dir.join(&plugin_url_hash);
         let plugin_path = plugin_dir.join("plugin.wasm");
+        let signature_path = plugin_dir.join(format!("plugin.wasm.{SIGNATURE_EXTENSION}"));
 
         if plugin_path.exists() {
+            let plugin_contents = std::fs::read(&plugin_path)?;
             if let Some(sha256) = self.plugin.sha256.to_owned() {
-                let plugin_contents = std::fs::read(&plugin_path)?;
                 let plugin_hash = sha256::digest(&plugin_contents);
                 if sha256 != plugin_hash {
-                    return Err(anyhow::anyhow!("The SHA256 hash of the plugin doesn't match the hash within the {CHANGESET_DIRECTORY}/{CHANGESET_DIRECTORY} file"));
+                    return Err(anyhow::anyhow!(
+                        "The SHA256 hash of the plugin doesn't match the hash within the {CHANGESET_DIRECTORY}/{CONFIG_FILENAME} file"
+                    ));
+                }
+            }
+
+            // Plugins cached before `public_keys` was set don't have a cached signature
+            let signature = match std::fs::read_to_string(&signature_path).ok() {
+                None if !self.plugin.public_keys.is_empty() => {
+                    download_signature(&self.plugin.get_signature_url()?)?
                 }
+                signature => signature,
+            };
+            self.plugin
+                .verify_signature(&plugin_contents, signature.as_deref())?;
+            if let Some(signature) = signature.filter(|_| !signature_path.exists()) {
+                std::fs::write(&signature_path, signature)?;
             }
 
             return Ok(plugin_path);
@@ -95,17 +302,45 @@ impl Config {
             .plugin
             .sha256
             .as_ref()
-            .map_or(true, |sha256| sha256 == &download_checksum);
+            .is_none_or(|sha256| sha256 == &download_checksum);
 
         if !checksum_matches {
-            return Err(anyhow::anyhow!("The SHA256 hash of the plugin downloaded doesn't match the hash within the {CHANGESET_DIRECTORY}/{CHANGESET_DIRECTORY} file"));
+            return Err(anyhow::anyhow!(
+                "The SHA256 hash of the plugin downloaded doesn't match the hash within the {CHANGESET_DIRECTORY}/{CONFIG_FILENAME} file"
+            ));
         }
 
+        let signature = if self.plugin.public_keys.is_empty() {
+            None
+        } else {
+            download_signature(&self.plugin.get_signature_url()?)?
+        };
+        self.plugin.verify_signature(&body, signature.as_deref())?;
+
+        if let Some(signature) = signature {
+            std::fs::write(&signature_path, signature)?;
+        }
         std::fs::write(&plugin_path, body)?;
         Ok(plugin_path)
     }
 }
 
+/// Downloads the detached signature of a plugin, returning `None` when one hasn't been published
+fn download_signature(signature_url: &str) -> anyhow::Result<Option<String>> {
+    let response = reqwest::blocking::get(signature_url)?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "An error occurred whilst downloading the plugin signature: {}",
+            response.status()
+        ));
+    }
+
+    Ok(Some(response.text()?))
+}
+
 pub fn get_config() -> anyhow::Result<Config> {
     let filepath = PathBuf::from(CHANGESET_DIRECTORY).join(CONFIG_FILENAME);
     if !PathBuf::from(CHANGESET_DIRECTORY).exists() {
@@ -120,9 +355,21 @@ pub fn get_config() -> anyhow::Result<Config> {
     Ok(config)
 }
 
+/// Retrieves the config, returning `None` when the project hasn't been configured. Used by
+/// commands which don't require the plugin
+pub fn get_config_if_exists() -> anyhow::Result<Option<Config>> {
+    let filepath = PathBuf::from(CHANGESET_DIRECTORY).join(CONFIG_FILENAME);
+    if !filepath.exists() {
+        return Ok(None);
+    }
+
+    get_config().map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
     use rstest::rstest;
 
     #[rstest]
@@ -142,8 +389,41 @@ mod tests {
         let plugin = Plugin {
             url: input.to_string(),
             sha256: None,
+            public_keys: vec![],
+            permissions: None,
         };
         let result = plugin.get_url().unwrap();
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case(
+        r#"{"plugin": {"url": "gh:owner/repo@version"}}"#,
+        VersionScheme::Semver
+    )]
+    #[case(
+        r#"{"plugin": {"url": "gh:owner/repo@version"}, "versioning": {"scheme": "semver"}}"#,
+        VersionScheme::Semver
+    )]
+    #[case(
+        r#"{"plugin": {"url": "gh:owner/repo@version"}, "versioning": {"scheme": "plugin"}}"#,
+        VersionScheme::Plugin
+    )]
+    fn test_config_versioning(#[case] input: &str, #[case] expected: VersionScheme) {
+        let config: Config = serde_json::from_str(input).unwrap();
+
+        assert_eq!(config.versioning, expected);
+    }
+
+    #[rstest]
+    #[case(r#"{"plugin": {"url": "gh:owner/repo@version"}}"#, ChangeSource::Files)]
+    #[case(
+        r#"{"plugin": {"url": "gh:owner/repo@version"}, "changesets": {"source": "commits"}}"#,
+        ChangeSource::Commits
+    )]
+    fn test_config_change_source(#[case] input: &str, #[case] expected: ChangeSource) {
+        let config: Config = serde_json::from_str(input).unwrap();
+
+        assert_eq!(config.changesets.source, expected);
+    }
 }
@@ -0,0 +1,386 @@
+use crate::changeset::{Change, IncrementType};
+use crate::git::{self, LogEntry};
+use std::path::Path;
+
+const BREAKING_CHANGE_FOOTERS: [&str; 2] = ["BREAKING CHANGE:", "BREAKING-CHANGE:"];
+
+/// A commit message following the Conventional Commits specification, e.g.
+/// `feat(parser)!: support nested lists`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+    /// The body of the commit, excluding any trailers such as `Signed-off-by`
+    pub body: String,
+    /// The pull request the commit was merged in, taken from a trailing `(#123)`
+    pub pr: Option<u64>,
+}
+
+impl ConventionalCommit {
+    /// The increment the commit requires, `None` for types which aren't released, e.g. `chore`
+    pub fn increment_type(&self) -> Option<IncrementType> {
+        if self.breaking {
+            return Some(IncrementType::Major);
+        }
+        match self.kind.to_lowercase().as_str() {
+            "feat" => Some(IncrementType::Minor),
+            "fix" => Some(IncrementType::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the line is a git trailer, e.g. `Signed-off-by: Alice` or `Refs #123`
+fn is_trailer(line: &str) -> bool {
+    if BREAKING_CHANGE_FOOTERS.iter().any(|f| line.starts_with(f)) {
+        return true;
+    }
+    let token = line.split([':', ' ']).next().unwrap_or_default();
+    let rest = &line[token.len()..];
+    !token.is_empty()
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && (rest.starts_with(": ") || rest.starts_with(" #"))
+}
+
+/// Removes the trailing `(#123)` GitHub adds to squash merged commits, returning the number
+fn strip_pr_suffix(description: &str) -> (&str, Option<u64>) {
+    let pr = description
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once("(#"))
+        .and_then(|(rest, number)| Some((rest.trim_end(), number.parse().ok()?)));
+    match pr {
+        Some((rest, number)) => (rest, Some(number)),
+        None => (description, None),
+    }
+}
+
+/// Parses the commit message, returning `None` if it doesn't follow the specification
+pub fn parse_commit_message(message: &str) -> Option<ConventionalCommit> {
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    let (prefix, description) = subject.trim().split_once(": ")?;
+
+    let (prefix, bang) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?.trim().to_string())),
+        None => (prefix, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let breaking = bang
+        || body
+            .lines()
+            .any(|line| BREAKING_CHANGE_FOOTERS.iter().any(|f| line.starts_with(f)));
+
+    // Trailers are only found within the final paragraph of the body. The explanation within a
+    // breaking change footer is kept, as it's what the release's readers most need
+    let mut paragraphs = body
+        .trim()
+        .split("\n\n")
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if paragraphs
+        .last()
+        .is_some_and(|p| !p.is_empty() && p.lines().all(is_trailer))
+    {
+        let trailers = paragraphs.pop().unwrap_or_default();
+        paragraphs.extend(trailers.lines().filter_map(|line| {
+            BREAKING_CHANGE_FOOTERS
+                .iter()
+                .find_map(|f| line.strip_prefix(f))
+                .map(|explanation| explanation.trim().to_string())
+        }));
+    }
+
+    let (description, pr) = strip_pr_suffix(description.trim());
+    Some(ConventionalCommit {
+        kind: kind.to_string(),
+        scope: scope.filter(|scope| !scope.is_empty()),
+        breaking,
+        description: description.to_string(),
+        body: paragraphs.join("\n\n").trim().to_string(),
+        pr,
+    })
+}
+
+/// Capitalises the first letter, as commit descriptions are conventionally lowercase
+fn capitalise(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn change_from_commit(entry: &LogEntry, commit: &ConventionalCommit) -> Option<Change> {
+    Some(Change {
+        bump_type: commit.increment_type()?,
+        summary: capitalise(&commit.description),
+        description: commit.body.clone(),
+        git_authors: vec![entry.commit.author.clone()],
+        pr: commit.pr,
+        commits: vec![entry.commit.clone()],
+        ..Default::default()
+    })
+}
+
+/// Combines the changes made within a scope into a single change, listing each of them
+fn change_from_scope(scope: &str, changes: Vec<Change>) -> Change {
+    let mut git_authors: Vec<String> = vec![];
+    for author in changes.iter().flat_map(|c| &c.git_authors) {
+        if !git_authors.contains(author) {
+            git_authors.push(author.clone());
+        }
+    }
+    let description = changes
+        .iter()
+        .map(|c| match c.pr {
+            Some(pr) => format!("- {} (#{pr})", c.summary),
+            None => format!("- {}", c.summary),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Change {
+        bump_type: changes
+            .iter()
+            .map(|c| c.bump_type.clone())
+            .max()
+            .unwrap_or_default(),
+        summary: format!("Changes to {scope}"),
+        description,
+        git_authors,
+        ..Default::default()
+    }
+}
+
+/// Creates a change for each released commit, i.e. `feat`, `fix` or breaking commits. When
+/// `per_scope` is set, the commits sharing a scope are combined into a single change
+pub fn changes_from_commits(entries: &[LogEntry], per_scope: bool) -> Vec<Change> {
+    let mut changes = vec![];
+    // Scopes are kept in the order they first appeared, alongside the index of their change
+    let mut scopes: Vec<(String, usize, Vec<Change>)> = vec![];
+
+    for entry in entries {
+        let Some(commit) = parse_commit_message(&entry.message) else {
+            continue;
+        };
+        let Some(change) = change_from_commit(entry, &commit) else {
+            continue;
+        };
+
+        match commit.scope.filter(|_| per_scope) {
+            Some(scope) => match scopes.iter_mut().find(|(s, _, _)| *s == scope) {
+                Some((_, _, scoped)) => scoped.push(change),
+                None => {
+                    scopes.push((scope, changes.len(), vec![change.clone()]));
+                    changes.push(change);
+                }
+            },
+            None => changes.push(change),
+        }
+    }
+
+    // Scopes containing a single commit are left as is
+    for (scope, index, scoped) in scopes {
+        if scoped.len() > 1 {
+            changes[index] = change_from_scope(&scope, scoped);
+        }
+    }
+
+    changes
+}
+
+/// The changes made by the commits since the latest release, i.e. the latest tag matching one of
+/// the `release_tags` patterns. Every commit is used when nothing has been released yet
+pub fn changes_since_latest_release(
+    repository: &Path,
+    release_tags: &[String],
+) -> anyhow::Result<Vec<Change>> {
+    let latest_release = git::latest_tag(repository, release_tags);
+    let entries = git::log_since(repository, latest_release.as_deref())?;
+
+    Ok(changes_from_commits(&entries, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::Commit;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::feature("feat: add lists", "feat", None, false, Some(IncrementType::Minor))]
+    #[case::fix_with_scope(
+        "fix(parser): handle tabs",
+        "fix",
+        Some("parser"),
+        false,
+        Some(IncrementType::Patch)
+    )]
+    #[case::breaking_bang(
+        "feat(api)!: remove v1",
+        "feat",
+        Some("api"),
+        true,
+        Some(IncrementType::Major)
+    )]
+    #[case::breaking_footer(
+        "fix: rename flag\n\nBREAKING CHANGE: `--old` is now `--new`",
+        "fix",
+        None,
+        true,
+        Some(IncrementType::Major)
+    )]
+    #[case::unreleased_type("chore: bump deps", "chore", None, false, None)]
+    fn test_parse_commit_message(
+        #[case] message: &str,
+        #[case] kind: &str,
+        #[case] scope: Option<&str>,
+        #[case] breaking: bool,
+        #[case] increment_type: Option<IncrementType>,
+    ) {
+        let commit = parse_commit_message(message).unwrap();
+
+        assert_eq!(commit.kind, kind);
+        assert_eq!(commit.scope.as_deref(), scope);
+        assert_eq!(commit.breaking, breaking);
+        assert_eq!(commit.increment_type(), increment_type);
+    }
+
+    #[rstest]
+    #[case("Add lists")]
+    #[case("Merge branch 'main' into feature")]
+    #[case("feat(parser: add lists")]
+    #[case("feat:missing space")]
+    fn test_parse_commit_message_rejects_non_conventional_commits(#[case] message: &str) {
+        assert_eq!(parse_commit_message(message), None);
+    }
+
+    #[rstest]
+    fn test_parse_commit_message_strips_pr_and_trailers() {
+        let message = "feat: add lists (#42)\n\nLists can now be nested.\n\nSigned-off-by: Alice <alice@example.com>\nRefs #12\n";
+
+        let commit = parse_commit_message(message).unwrap();
+
+        assert_eq!(commit.description, "add lists");
+        assert_eq!(commit.pr, Some(42));
+        assert_eq!(commit.body, "Lists can now be nested.");
+    }
+
+    #[rstest]
+    #[case::footer_only(
+        "fix: rename flag\n\nBREAKING CHANGE: `--old` is now `--new`",
+        "`--old` is now `--new`"
+    )]
+    #[case::footer_alongside_trailers(
+        "feat!: remove v1\n\nThe v1 API has been removed.\n\nBREAKING-CHANGE: Use the v2 API instead\nSigned-off-by: Alice <alice@example.com>",
+        "The v1 API has been removed.\n\nUse the v2 API instead"
+    )]
+    fn test_parse_commit_message_keeps_breaking_change_explanation(
+        #[case] message: &str,
+        #[case] expected: &str,
+    ) {
+        let commit = parse_commit_message(message).unwrap();
+
+        assert_eq!(commit.body, expected);
+    }
+
+    fn entry(author: &str, message: &str) -> LogEntry {
+        LogEntry {
+            commit: Commit {
+                sha: "0123456789abcdef".to_string(),
+                author: author.to_string(),
+                date: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            },
+            message: message.to_string(),
+        }
+    }
+
+    #[rstest]
+    #[case::per_commit(false, vec![
+        ("Add lists", IncrementType::Minor, ""),
+        ("Handle tabs", IncrementType::Patch, ""),
+        ("Nest lists", IncrementType::Patch, ""),
+        ("Remove v1", IncrementType::Major, ""),
+    ])]
+    #[case::per_scope(true, vec![
+        ("Add lists", IncrementType::Minor, ""),
+        ("Changes to parser", IncrementType::Patch, "- Handle tabs (#4)\n- Nest lists"),
+        ("Remove v1", IncrementType::Major, ""),
+    ])]
+    fn test_changes_from_commits(
+        #[case] per_scope: bool,
+        #[case] expected: Vec<(&str, IncrementType, &str)>,
+    ) {
+        let entries = vec![
+            entry("Alice", "feat: add lists"),
+            entry("Bob", "fix(parser): handle tabs (#4)"),
+            entry("Bob", "chore: bump dependencies"),
+            entry("Carol", "fix(parser): nest lists"),
+            entry("Alice", "feat(api)!: remove v1"),
+            entry("Alice", "Update README"),
+        ];
+
+        let changes = changes_from_commits(&entries, per_scope);
+
+        let changes = changes
+            .iter()
+            .map(|c| {
+                (
+                    c.summary.as_str(),
+                    c.bump_type.clone(),
+                    c.description.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(changes, expected);
+    }
+
+    #[rstest]
+    fn test_changes_since_latest_release() {
+        let directory = tempfile::tempdir().unwrap();
+        let repository = directory.path().to_string_lossy().to_string();
+        let run = |args: &[&str]| {
+            let identity = [
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+            ];
+            git::git(&[&["-C", &repository], &identity[..], args].concat()).unwrap();
+        };
+        run(&["init", "--quiet"]);
+        let commit = |message: &str| {
+            run(&[
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "--no-gpg-sign",
+                "-m",
+                message,
+            ]);
+        };
+        commit("feat: add lists");
+        run(&["tag", "v1.0.0"]);
+        commit("fix: handle tabs");
+        run(&["tag", "nightly"]);
+        commit("feat: nest lists");
+
+        let changes =
+            changes_since_latest_release(directory.path(), &["v[0-9]*".to_string()]).unwrap();
+
+        let summaries = changes
+            .iter()
+            .map(|c| c.summary.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(summaries, vec!["Handle tabs", "Nest lists"]);
+    }
+}
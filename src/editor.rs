@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::Command;
+
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Retrieves the user's preferred editor from `$VISUAL` or `$EDITOR`, falling back to `vi`
+fn get_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or(DEFAULT_EDITOR.to_string())
+}
+
+/// Opens the file within the user's editor, blocking until the editor is closed
+pub fn open_in_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = get_editor();
+    // The editor may include arguments, e.g. `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_EDITOR);
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| anyhow::anyhow!("Failed to launch the editor `{editor}`: {e}"))?;
+
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "The editor `{editor}` exited with {status}"
+        ));
+    }
+
+    Ok(())
+}
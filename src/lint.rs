@@ -0,0 +1,178 @@
+use crate::changeset::Change;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Warning,
+    Error,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Warning => write!(f, "warning"),
+            Level::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A problem found with one or more changesets
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    /// The changeset file the problem was found in, if any
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    #[serde(default)]
+    pub line: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, file: Option<PathBuf>, line: Option<usize>) -> Self {
+        Diagnostic {
+            level: Level::Error,
+            message,
+            file,
+            line,
+        }
+    }
+}
+
+/// Reports changesets which share the same summary, as they're indistinguishable in the changelog
+pub fn find_duplicate_summaries(changesets: &[Change]) -> Vec<Diagnostic> {
+    changesets
+        .iter()
+        .enumerate()
+        .filter_map(|(index, change)| {
+            let original = changesets[..index]
+                .iter()
+                .find(|c| c.summary == change.summary && c.package == change.package)?;
+            Some(Diagnostic::error(
+                format!(
+                    "The summary `{}` is the same as the summary of {}",
+                    change.summary,
+                    original.file_path.display()
+                ),
+                Some(change.file_path.clone()),
+                None,
+            ))
+        })
+        .collect()
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.level, self.message)?;
+        if let Some(file) = &self.file {
+            write!(f, "\n  --> {}", file.display())?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Prints the diagnostics, returning an error if any of them are errors
+pub fn report(diagnostics: &[Diagnostic]) -> anyhow::Result<()> {
+    for diagnostic in diagnostics {
+        eprintln!("{diagnostic}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Error)
+        .count();
+    if errors > 0 {
+        return Err(anyhow::anyhow!(
+            "Found {errors} problem(s) with the changesets"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Diagnostic {
+        level: Level::Error,
+        message: "Major bumps aren't allowed before 1.0.0".to_string(),
+        file: Some(PathBuf::from(".changeset/dog-cat-owl.md")),
+        line: Some(2),
+    }, "error: Major bumps aren't allowed before 1.0.0\n  --> .changeset/dog-cat-owl.md:2")]
+    #[case(Diagnostic {
+        level: Level::Warning,
+        message: "Breaking changes should have a description".to_string(),
+        file: Some(PathBuf::from(".changeset/dog-cat-owl.md")),
+        line: None,
+    }, "warning: Breaking changes should have a description\n  --> .changeset/dog-cat-owl.md")]
+    #[case(Diagnostic {
+        level: Level::Warning,
+        message: "There aren't any changesets".to_string(),
+        file: None,
+        line: None,
+    }, "warning: There aren't any changesets")]
+    fn test_diagnostic_display(#[case] diagnostic: Diagnostic, #[case] expected: &str) {
+        assert_eq!(diagnostic.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(vec![], true)]
+    #[case(vec![Diagnostic {
+        level: Level::Warning,
+        message: "warning".to_string(),
+        file: None,
+        line: None,
+    }], true)]
+    #[case(vec![Diagnostic {
+        level: Level::Error,
+        message: "error".to_string(),
+        file: None,
+        line: None,
+    }], false)]
+    fn test_report_only_fails_on_errors(
+        #[case] diagnostics: Vec<Diagnostic>,
+        #[case] expected_ok: bool,
+    ) {
+        let result = report(&diagnostics);
+
+        assert_eq!(result.is_ok(), expected_ok);
+    }
+
+    #[rstest]
+    fn test_find_duplicate_summaries() {
+        let change = |name: &str, summary: &str, package: Option<&str>| Change {
+            file_path: PathBuf::from(format!(".changeset/{name}.md")),
+            summary: summary.to_string(),
+            package: package.map(String::from),
+            ..Default::default()
+        };
+        let changesets = vec![
+            change("first", "Fixed a bug", None),
+            change("second", "Added a feature", None),
+            change("third", "Fixed a bug", None),
+            change("fourth", "Fixed a bug", Some("core")),
+        ];
+
+        let diagnostics = find_duplicate_summaries(&changesets);
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::error(
+                "The summary `Fixed a bug` is the same as the summary of .changeset/first.md"
+                    .to_string(),
+                Some(PathBuf::from(".changeset/third.md")),
+                None,
+            )]
+        );
+    }
+}
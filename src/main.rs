@@ -1,36 +1,61 @@
 use clap::Parser;
 use cli::{
-    add_changeset, get_version, preview_version_command, version_command, AddCommand, Cli,
-    Commands, PreviewCommands,
+    AddCommand, Cli, Commands, PreviewCommands, add_changeset, edit_command, from_commits_command,
+    get_version, lint_command, list_command, preview_version_command, remove_command,
+    revert_version_command, show_command, version_command,
 };
 
+mod calver;
 mod changelog;
 mod changeset;
 mod cli;
 mod config;
+mod conventional;
+mod editor;
+mod git;
+mod lint;
 mod plugin;
+mod sandbox;
+mod signature;
+mod template;
 mod utils;
+mod versioning;
 
 fn main() {
     let cli = Cli::parse();
 
-    match &cli.command {
+    let result = match &cli.command {
         Some(Commands::Add(command)) => add_changeset(command),
-        Some(Commands::Version(_command)) => version_command().unwrap(),
-        Some(Commands::Get(_)) => {
-            get_version();
-        }
+        Some(Commands::Version(command)) if command.revert => revert_version_command(),
+        Some(Commands::Version(_command)) => version_command(),
+        Some(Commands::Get(_)) => get_version().map(|_| ()),
         Some(Commands::Preview(command)) => match &command.preview_commands {
-            PreviewCommands::Version(_) => {
-                get_version();
-            }
-            PreviewCommands::Changelog => {
-                preview_version_command();
-            }
+            PreviewCommands::Version(_) => get_version().map(|_| ()),
+            PreviewCommands::Changelog => preview_version_command(),
         },
+        Some(Commands::Lint(_)) => lint_command(),
+        Some(Commands::List(command)) => list_command(command),
+        Some(Commands::Show(command)) => show_command(command),
+        Some(Commands::Edit(command)) => edit_command(command),
+        Some(Commands::Remove(command)) => remove_command(command),
+        Some(Commands::FromCommits(command)) => from_commits_command(command),
         None => add_changeset(&AddCommand {
             increment_type: None,
             message: None,
+            description: None,
+            description_file: None,
+            name: None,
+            package: None,
+            edit: false,
+            no_interactive: false,
+            json: false,
+            template: None,
+            format: Default::default(),
         }),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {e:#}");
+        std::process::exit(1);
     }
 }
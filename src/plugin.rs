@@ -1,50 +1,320 @@
-use super::config::get_config;
+use super::changeset::{Change, IncrementType};
+use super::config::{Config, Permissions, get_cache_dir};
+use super::lint::Diagnostic;
+use super::sandbox::Sandbox;
 use extism::*;
 use extism_convert::Json;
-use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 
-pub fn setup_plugin(read_only: bool) -> anyhow::Result<Plugin> {
-    let config = get_config()?;
-    let cached_plugin_path = config.cache_plugin_from_url()?;
+/// The error envelope a plugin can return (as a JSON encoded extism error) to surface an
+/// actionable error to the user rather than an opaque error string
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct PluginError {
+    /// A machine readable identifier of the error, e.g. `manifest_not_found`
+    pub code: String,
+    pub message: String,
+    /// The file the error relates to, if any
+    pub file: Option<String>,
+    /// A suggestion on how the user can resolve the error
+    pub hint: Option<String>,
+}
 
-    let plugin_file = Wasm::file(cached_plugin_path);
+impl Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)?;
+        if let Some(file) = &self.file {
+            write!(f, "\n  --> {file}")?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\n  hint: {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// Converts the error returned by a plugin call into a [`PluginError`] when the plugin returned
+/// the JSON error envelope, otherwise adds context about which function failed
+fn decode_plugin_error(function: &str, error: Error) -> anyhow::Error {
+    let plugin_error = error
+        .chain()
+        .find_map(|cause| serde_json::from_str::<PluginError>(&cause.to_string()).ok());
+
+    match plugin_error {
+        Some(plugin_error) => plugin_error.into(),
+        None => error.context(format!("The plugin failed whilst calling `{function}`")),
+    }
+}
+
+/// A plugin along with the sandbox its filesystem access is restricted to, if any
+pub struct PluginInstance {
+    plugin: Plugin,
+    sandbox: Option<Sandbox>,
+}
 
-    let mut current_dir = std::env::current_dir()?.to_str().unwrap().to_string();
+fn call_plugin<'a, 'b, T: ToBytes<'a>, U: FromBytes<'b>>(
+    instance: &'b mut PluginInstance,
+    function: &str,
+    input: T,
+) -> anyhow::Result<U> {
+    let output = instance
+        .plugin
+        .call::<T, U>(function, input)
+        .map_err(|e| decode_plugin_error(function, e))?;
+
+    if let Some(sandbox) = &instance.sandbox {
+        sandbox.sync()?;
+    }
 
+    Ok(output)
+}
+
+/// Writes a wasmtime cache config so the compiled module is stored on disk rather than the wasm
+/// being recompiled on every run. The cache is keyed by the hash of the wasm and the extism version
+fn get_compilation_cache_config(cache_dir: &Path, plugin_path: &Path) -> anyhow::Result<PathBuf> {
+    let wasm_hash = sha256::try_digest(plugin_path)?;
+    let extism_version = extism_version().trim_end_matches('\0');
+    let compiled_dir = cache_dir.join("compiled").join(extism_version);
+    let cache_dir = compiled_dir.join(&wasm_hash);
+    let cache_config_path = compiled_dir.join(format!("{wasm_hash}.toml"));
+
+    if !cache_config_path.exists() {
+        std::fs::create_dir_all(&cache_dir)?;
+        let cache_dir = cache_dir.to_string_lossy();
+        std::fs::write(
+            &cache_config_path,
+            format!("[cache]\nenabled = true\ndirectory = {cache_dir:?}\n"),
+        )?;
+    }
+
+    Ok(cache_config_path)
+}
+
+fn with_mount(manifest: Manifest, host: &Path, guest: &str, read_only: bool) -> Manifest {
+    let mut host = host.to_string_lossy().to_string();
     if read_only {
-        current_dir = format!("ro:{}", current_dir);
+        host = format!("ro:{}", host);
     }
 
-    let manifest = extism::Manifest::new([plugin_file])
-        // Mounting to the root as the plugin is expected to be in the root of the fs
-        .with_allowed_path(current_dir, "/");
+    manifest.with_allowed_path(host, guest)
+}
+
+/// Mounts only the paths listed in the permissions, via a sandbox, along with any extra mounts
+fn with_permissions(
+    manifest: Manifest,
+    current_dir: &Path,
+    permissions: &Permissions,
+    read_only: bool,
+) -> anyhow::Result<(Manifest, Sandbox)> {
+    // When read only, the writable paths are still readable but are never copied back
+    let sandbox = match read_only {
+        true => {
+            let read = [permissions.read.clone(), permissions.write.clone()].concat();
+            Sandbox::new(current_dir, &read, &[])?
+        }
+        false => Sandbox::new(current_dir, &permissions.read, &permissions.write)?,
+    };
+
+    // Mounting to the root as the plugin is expected to be in the root of the fs
+    let mut manifest = with_mount(manifest, sandbox.path(), "/", read_only);
+    for mount in &permissions.mounts {
+        let host = current_dir.join(&mount.host);
+        manifest = with_mount(manifest, &host, &mount.guest, mount.read_only);
+    }
 
-    let plugin = extism::Plugin::new(manifest, [], true).unwrap();
-    return Ok(plugin);
+    Ok((manifest, sandbox))
 }
 
-pub fn get_version_via_plugin() -> anyhow::Result<Version> {
-    let mut plugin = setup_plugin(true)?;
-    let response = plugin.call::<&str, &str>("get_version", "")?;
+/// Sets up the plugin. The returned plugin should be reused for every call made within a command
+pub fn setup_plugin(config: &Config, read_only: bool) -> anyhow::Result<PluginInstance> {
+    let cached_plugin_path = config.cache_plugin_from_url()?;
+    let cache_config_path = get_compilation_cache_config(&get_cache_dir()?, &cached_plugin_path)?;
+
+    let plugin_file = Wasm::file(cached_plugin_path);
+
+    let current_dir = std::env::current_dir()?;
 
-    let parsed_version = Version::parse(response.to_string().as_str())?;
+    let manifest = extism::Manifest::new([plugin_file]);
+    let (manifest, sandbox) = match &config.plugin.permissions {
+        Some(permissions) => {
+            let (manifest, sandbox) =
+                with_permissions(manifest, &current_dir, permissions, read_only)?;
+            (manifest, Some(sandbox))
+        }
+        // Mounting to the root as the plugin is expected to be in the root of the fs
+        None => (with_mount(manifest, &current_dir, "/", read_only), None),
+    };
 
-    Ok(parsed_version)
+    let plugin = PluginBuilder::new(manifest)
+        .with_wasi(true)
+        .with_cache_config(cache_config_path)
+        .build()?;
+    return Ok(PluginInstance { plugin, sandbox });
 }
 
-#[derive(Debug, serde::Serialize)]
+pub fn get_version_via_plugin(plugin: &mut PluginInstance) -> anyhow::Result<String> {
+    let response = call_plugin::<&str, &str>(plugin, "get_version", "")?;
+
+    Ok(response.trim().to_string())
+}
+
+#[derive(Debug, Serialize)]
 struct SetVersionRequest {
     pub version: String,
 }
 
-pub fn set_version_via_plugin(version: &Version) -> anyhow::Result<()> {
-    let mut plugin = setup_plugin(false)?;
-
+pub fn set_version_via_plugin(plugin: &mut PluginInstance, version: &str) -> anyhow::Result<()> {
     let request = SetVersionRequest {
         version: version.to_string(),
     };
 
-    plugin.call::<Json<SetVersionRequest>, &str>("set_version", request.into())?;
+    call_plugin::<Json<SetVersionRequest>, &str>(plugin, "set_version", request.into())?;
 
     Ok(())
 }
+
+#[derive(Debug, Serialize)]
+struct BumpVersionRequest<'a> {
+    pub version: &'a str,
+    pub bump_type: &'a IncrementType,
+}
+
+/// Determines the next version via the plugin's `bump_version` export, used when the version
+/// isn't a semantic version
+pub fn bump_version_via_plugin(
+    plugin: &mut PluginInstance,
+    version: &str,
+    bump_type: &IncrementType,
+) -> anyhow::Result<String> {
+    if !plugin.plugin.function_exists("bump_version") {
+        return Err(anyhow::anyhow!(
+            "The plugin doesn't export `bump_version`, which is required by the `plugin` versioning scheme"
+        ));
+    }
+
+    let request = BumpVersionRequest { version, bump_type };
+
+    let response =
+        call_plugin::<Json<BumpVersionRequest>, &str>(plugin, "bump_version", Json(request))?;
+
+    Ok(response.trim().to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateChangesetsRequest<'a> {
+    pub version: String,
+    pub changesets: &'a [Change],
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidateChangesetsResponse {
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Validates the changesets against the rules of the ecosystem via the optional
+/// `validate_changesets` export. Plugins which don't export it accept every changeset
+pub fn validate_changesets_via_plugin(
+    plugin: &mut PluginInstance,
+    version: &str,
+    changesets: &[Change],
+) -> anyhow::Result<Vec<Diagnostic>> {
+    if !plugin.plugin.function_exists("validate_changesets") {
+        return Ok(vec![]);
+    }
+
+    let request = ValidateChangesetsRequest {
+        version: version.to_string(),
+        changesets,
+    };
+
+    let Json(response) = call_plugin::<
+        Json<ValidateChangesetsRequest>,
+        Json<ValidateChangesetsResponse>,
+    >(plugin, "validate_changesets", Json(request))?;
+
+    Ok(response.diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::envelope(
+        Error::msg(r#"{"code": "manifest_not_found", "message": "Cargo.toml wasn't found", "file": "Cargo.toml", "hint": "Run the command from the root of the crate"}"#),
+        "Cargo.toml wasn't found (manifest_not_found)\n  --> Cargo.toml\n  hint: Run the command from the root of the crate"
+    )]
+    #[case::envelope_without_optional_fields(
+        Error::msg(r#"{"code": "invalid_version", "message": "The version is invalid"}"#),
+        "The version is invalid (invalid_version)"
+    )]
+    #[case::envelope_with_context(
+        Error::msg(r#"{"code": "invalid_version", "message": "The version is invalid"}"#).context("wasm backtrace"),
+        "The version is invalid (invalid_version)"
+    )]
+    #[case::plain_string(
+        Error::msg("something went wrong"),
+        "The plugin failed whilst calling `get_version`: something went wrong"
+    )]
+    fn test_decode_plugin_error(#[case] error: Error, #[case] expected: &str) {
+        let result = decode_plugin_error("get_version", error);
+
+        assert_eq!(format!("{result:#}"), expected);
+    }
+
+    /// The compiled modules within the directory, alongside when they were last modified. The
+    /// usage stats and cleanup markers wasmtime updates in the background are skipped
+    fn list_compiled_modules(directory: &Path) -> Vec<(PathBuf, std::time::SystemTime)> {
+        let mut files = vec![];
+        let mut directories = vec![directory.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                if path.is_dir() {
+                    directories.push(path);
+                } else if !name.starts_with('.')
+                    && !name.ends_with(".stats")
+                    && !name.ends_with(".toml")
+                {
+                    let modified = path.metadata().unwrap().modified().unwrap();
+                    files.push((path, modified));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[rstest]
+    fn test_compiled_module_is_cached_and_reused() {
+        let directory = tempfile::tempdir().unwrap();
+        let plugin_path = directory.path().join("plugin.wasm");
+        // The smallest valid module, just the magic number and version
+        std::fs::write(&plugin_path, b"\0asm\x01\0\0\0").unwrap();
+        let cache_dir = directory.path().join("cache");
+        let build = || {
+            let cache_config_path = get_compilation_cache_config(&cache_dir, &plugin_path).unwrap();
+            let manifest = Manifest::new([Wasm::file(&plugin_path)]);
+            PluginBuilder::new(manifest)
+                .with_wasi(true)
+                .with_cache_config(cache_config_path)
+                .build()
+                .unwrap();
+        };
+
+        build();
+        let cached = list_compiled_modules(&cache_dir);
+        build();
+
+        // The second setup loads the modules compiled by the first rather than rewriting them
+        assert!(!cached.is_empty());
+        assert_eq!(list_compiled_modules(&cache_dir), cached);
+    }
+}
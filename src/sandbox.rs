@@ -0,0 +1,173 @@
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+
+/// A staging directory which only contains the paths a plugin has been given access to. Only the
+/// writable paths are copied back into the project, anything else the plugin writes is discarded
+pub struct Sandbox {
+    root: PathBuf,
+    directory: TempDir,
+    write: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// Creates the sandbox, copying the `read` and `write` paths from the project `root` into it
+    pub fn new(root: &Path, read: &[PathBuf], write: &[PathBuf]) -> anyhow::Result<Sandbox> {
+        let directory = tempfile::tempdir()?;
+
+        for path in read.iter().chain(write) {
+            validate_path(path)?;
+            let source = root.join(path);
+            if source.exists() {
+                copy_path(&source, &directory.path().join(path))?;
+            }
+        }
+
+        Ok(Sandbox {
+            root: root.to_path_buf(),
+            directory,
+            write: write.to_vec(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.directory.path()
+    }
+
+    /// Copies the writable paths from the sandbox back into the project
+    pub fn sync(&self) -> anyhow::Result<()> {
+        for path in &self.write {
+            let staged = self.directory.path().join(path);
+            if staged.exists() {
+                copy_path(&staged, &self.root.join(path))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Ensures the path is relative to, and doesn't escape, the project root
+fn validate_path(path: &Path) -> anyhow::Result<()> {
+    let escapes_root = path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes_root {
+        return Err(anyhow::anyhow!(
+            "The plugin permission `{}` must be a path relative to the project root",
+            path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Copies a file, or a directory recursively, creating any missing parent directories. Symlinks
+/// are skipped, as they could point outside of the project or form a cycle
+fn copy_path(source: &Path, destination: &Path) -> anyhow::Result<()> {
+    let metadata = std::fs::symlink_metadata(source)?;
+    if metadata.is_symlink() {
+        return Ok(());
+    }
+    if metadata.is_dir() {
+        std::fs::create_dir_all(destination)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(source, destination)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+    use tempfile::tempdir;
+
+    #[rstest]
+    fn test_sandbox_only_contains_permitted_paths() {
+        let root = tempdir().unwrap();
+        std::fs::write(root.path().join("Cargo.toml"), "manifest").unwrap();
+        std::fs::write(root.path().join("Cargo.lock"), "lock").unwrap();
+        std::fs::create_dir(root.path().join("src")).unwrap();
+        std::fs::write(root.path().join("src").join("main.rs"), "fn main() {}").unwrap();
+
+        let sandbox = Sandbox::new(
+            root.path(),
+            &[PathBuf::from("Cargo.lock")],
+            &[PathBuf::from("Cargo.toml")],
+        )
+        .unwrap();
+
+        assert!(sandbox.path().join("Cargo.toml").exists());
+        assert!(sandbox.path().join("Cargo.lock").exists());
+        assert!(!sandbox.path().join("src").exists());
+    }
+
+    #[rstest]
+    fn test_sandbox_sync_only_copies_back_writable_paths() {
+        let root = tempdir().unwrap();
+        std::fs::write(root.path().join("Cargo.toml"), "version = 1").unwrap();
+        std::fs::write(root.path().join("Cargo.lock"), "version = 1").unwrap();
+
+        let sandbox = Sandbox::new(
+            root.path(),
+            &[PathBuf::from("Cargo.lock")],
+            &[PathBuf::from("Cargo.toml")],
+        )
+        .unwrap();
+        std::fs::write(sandbox.path().join("Cargo.toml"), "version = 2").unwrap();
+        std::fs::write(sandbox.path().join("Cargo.lock"), "version = 2").unwrap();
+        std::fs::write(sandbox.path().join("unexpected.txt"), "").unwrap();
+
+        sandbox.sync().unwrap();
+
+        let manifest = std::fs::read_to_string(root.path().join("Cargo.toml")).unwrap();
+        let lock = std::fs::read_to_string(root.path().join("Cargo.lock")).unwrap();
+        assert_eq!(manifest, "version = 2");
+        assert_eq!(lock, "version = 1");
+        assert!(!root.path().join("unexpected.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[rstest]
+    fn test_sandbox_skips_symlinks() {
+        let outside = tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        let root = tempdir().unwrap();
+        let src = root.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("main.rs"), "fn main() {}").unwrap();
+        std::os::unix::fs::symlink(outside.path().join("secret.txt"), src.join("secret.txt"))
+            .unwrap();
+        std::os::unix::fs::symlink(outside.path(), src.join("outside")).unwrap();
+        // A cycle, which would otherwise be copied forever
+        std::os::unix::fs::symlink(&src, src.join("cycle")).unwrap();
+
+        let sandbox = Sandbox::new(root.path(), &[PathBuf::from("src")], &[]).unwrap();
+
+        let staged = sandbox.path().join("src");
+        assert!(staged.join("main.rs").exists());
+        assert!(!staged.join("secret.txt").exists());
+        assert!(!staged.join("outside").exists());
+        assert!(!staged.join("cycle").exists());
+    }
+
+    #[rstest]
+    #[case("../Cargo.toml")]
+    #[case("/etc/passwd")]
+    fn test_sandbox_rejects_paths_outside_root(#[case] path: &str) {
+        let root = tempdir().unwrap();
+
+        let result = Sandbox::new(root.path(), &[PathBuf::from(path)], &[]);
+
+        assert!(result.is_err());
+    }
+}
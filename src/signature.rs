@@ -0,0 +1,103 @@
+use minisign_verify::{Error, PublicKey, Signature};
+use std::fmt::Display;
+
+/// The extension appended to the plugin url to locate its detached signature
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    /// No signature was published alongside the plugin
+    Unsigned,
+    /// The signature was made by a trusted key but doesn't match the plugin contents
+    BadSignature,
+    /// The signature is valid but was made by a key which isn't trusted
+    UntrustedKey,
+    /// One of the configured public keys couldn't be decoded
+    InvalidPublicKey(String),
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignatureError::Unsigned => write!(
+                f,
+                "The plugin is unsigned, no `.{SIGNATURE_EXTENSION}` file was found alongside it"
+            ),
+            SignatureError::BadSignature => {
+                write!(f, "The signature of the plugin is invalid")
+            }
+            SignatureError::UntrustedKey => write!(
+                f,
+                "The plugin was signed with a key that isn't listed in `plugin.public_keys`"
+            ),
+            SignatureError::InvalidPublicKey(key) => {
+                write!(
+                    f,
+                    "The public key `{key}` is not a valid minisign public key"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Verifies the detached minisign `signature` of the plugin `contents` against the trusted `public_keys`
+pub fn verify_signature(
+    public_keys: &[String],
+    contents: &[u8],
+    signature: Option<&str>,
+) -> Result<(), SignatureError> {
+    let signature = signature.ok_or(SignatureError::Unsigned)?;
+    let signature = Signature::decode(signature).map_err(|_| SignatureError::BadSignature)?;
+
+    for public_key in public_keys {
+        let decoded = PublicKey::from_base64(public_key)
+            .map_err(|_| SignatureError::InvalidPublicKey(public_key.clone()))?;
+
+        match decoded.verify(contents, &signature, false) {
+            Ok(_) => return Ok(()),
+            Err(Error::UnexpectedKeyId) => continue,
+            Err(_) => return Err(SignatureError::BadSignature),
+        }
+    }
+
+    Err(SignatureError::UntrustedKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const OTHER_PUBLIC_KEY: &str = "RWQBAgMEBQYHCAjDWCRnxAe1ROX/0mBLKcXkkDOkADl58OvXWNZrs8nV";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    #[rstest]
+    #[case::valid(vec![PUBLIC_KEY], b"test", Some(SIGNATURE), Ok(()))]
+    #[case::valid_with_multiple_keys(vec![OTHER_PUBLIC_KEY, PUBLIC_KEY], b"test", Some(SIGNATURE), Ok(()))]
+    #[case::unsigned(vec![PUBLIC_KEY], b"test", None, Err(SignatureError::Unsigned))]
+    #[case::tampered(vec![PUBLIC_KEY], b"Test", Some(SIGNATURE), Err(SignatureError::BadSignature))]
+    #[case::malformed(vec![PUBLIC_KEY], b"test", Some("not a signature"), Err(SignatureError::BadSignature))]
+    #[case::untrusted(vec![OTHER_PUBLIC_KEY], b"test", Some(SIGNATURE), Err(SignatureError::UntrustedKey))]
+    #[case::invalid_key(vec!["invalid"], b"test", Some(SIGNATURE), Err(SignatureError::InvalidPublicKey("invalid".to_string())))]
+    fn test_verify_signature(
+        #[case] public_keys: Vec<&str>,
+        #[case] contents: &[u8],
+        #[case] signature: Option<&str>,
+        #[case] expected: Result<(), SignatureError>,
+    ) {
+        let public_keys = public_keys
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>();
+
+        let result = verify_signature(&public_keys, contents, signature);
+
+        assert_eq!(result, expected);
+    }
+}
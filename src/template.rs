@@ -0,0 +1,109 @@
+use crate::config::ChangesetsConfig;
+use crate::utils;
+use std::path::PathBuf;
+
+/// The subdirectory of the changeset directory which templates are stored within
+pub const TEMPLATES_DIRECTORY: &str = "templates";
+const TEMPLATE_EXTENSION: &str = "md";
+
+/// The values substituted into a template's `{{summary}}`, `{{author}}` and `{{date}}`
+/// placeholders
+#[derive(Debug, Default)]
+pub struct TemplateValues {
+    pub summary: String,
+    pub author: String,
+    pub date: String,
+}
+
+fn templates_directory(config: &ChangesetsConfig) -> PathBuf {
+    config.directory.join(TEMPLATES_DIRECTORY)
+}
+
+/// The names of the templates within `<directory>/templates/`, sorted alphabetically
+pub fn list_templates(config: &ChangesetsConfig) -> anyhow::Result<Vec<String>> {
+    let directory = templates_directory(config);
+    if !directory.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut names = vec![];
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == TEMPLATE_EXTENSION)
+            && let Some(name) = path.file_stem()
+        {
+            names.push(name.to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Reads the template named `name`, e.g. `breaking` for `templates/breaking.md`
+pub fn load_template(config: &ChangesetsConfig, name: &str) -> anyhow::Result<String> {
+    utils::validate_file_stem(name)?;
+    let path = templates_directory(config).join(format!("{name}.{TEMPLATE_EXTENSION}"));
+    std::fs::read_to_string(&path).map_err(|e| {
+        let available = list_templates(config).unwrap_or_default();
+        match available.is_empty() {
+            true => anyhow::anyhow!("Unable to read the template {}: {e}", path.display()),
+            false => anyhow::anyhow!(
+                "Unable to read the template {}: {e}. The available templates are: {}",
+                path.display(),
+                available.join(", ")
+            ),
+        }
+    })
+}
+
+/// The placeholder for the changeset's summary
+pub const SUMMARY_PLACEHOLDER: &str = "{{summary}}";
+
+/// Substitutes the values into the template's placeholders
+pub fn render_template(template: &str, values: &TemplateValues) -> String {
+    template
+        .replace(SUMMARY_PLACEHOLDER, &values.summary)
+        .replace("{{author}}", &values.author)
+        .replace("{{date}}", &values.date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_render_template() {
+        let template = "## Migration guide for {{summary}}\n\nWritten by {{author}} on {{date}}.\n";
+        let values = TemplateValues {
+            summary: "Removed v1".to_string(),
+            author: "alice".to_string(),
+            date: "18-10-2026".to_string(),
+        };
+
+        assert_eq!(
+            render_template(template, &values),
+            "## Migration guide for Removed v1\n\nWritten by alice on 18-10-2026.\n"
+        );
+    }
+
+    #[rstest]
+    fn test_list_templates_only_lists_markdown_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let templates = directory.path().join(TEMPLATES_DIRECTORY);
+        std::fs::create_dir_all(&templates).unwrap();
+        for file_name in ["feature.md", "breaking.md", "notes.txt"] {
+            std::fs::write(templates.join(file_name), "").unwrap();
+        }
+        let config = ChangesetsConfig {
+            directory: directory.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            list_templates(&config).unwrap(),
+            vec!["breaking", "feature"]
+        );
+    }
+}
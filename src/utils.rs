@@ -8,6 +8,69 @@ pub fn insert_before(contents: &str, search: &str, insertable: &str) -> String {
     }
 }
 
+/// Formats the rows as a table with left aligned, space separated columns
+pub fn format_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(column, cell)| format!("{:width$}", cell, width = widths[column]))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats the duration as a short, human readable age, e.g. `3 days`
+pub fn format_age(duration: chrono::TimeDelta) -> String {
+    let (amount, unit) = if duration.num_days() >= 365 {
+        (duration.num_days() / 365, "year")
+    } else if duration.num_days() >= 30 {
+        (duration.num_days() / 30, "month")
+    } else if duration.num_days() >= 1 {
+        (duration.num_days(), "day")
+    } else if duration.num_hours() >= 1 {
+        (duration.num_hours(), "hour")
+    } else if duration.num_minutes() >= 1 {
+        (duration.num_minutes(), "minute")
+    } else {
+        return "just now".to_string();
+    };
+
+    match amount {
+        1 => format!("1 {unit}"),
+        _ => format!("{amount} {unit}s"),
+    }
+}
+
+/// Ensures the name of a file, without its extension, can't point outside of its directory
+pub fn validate_file_stem(name: &str) -> anyhow::Result<()> {
+    if name.trim().is_empty() {
+        return Err(anyhow::anyhow!("The name can't be empty"));
+    }
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(anyhow::anyhow!(
+            "The name `{name}` can't contain path separators or `..`"
+        ));
+    }
+
+    return Ok(());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,4 +97,52 @@ mod tests {
 
         assert_eq!(contents, "new line\nline1\nline2\nline3\n");
     }
+
+    #[rstest]
+    fn test_format_table_aligns_columns() {
+        let rows = vec![
+            vec![
+                "NAME".to_string(),
+                "TYPE".to_string(),
+                "SUMMARY".to_string(),
+            ],
+            vec![
+                "dog-cat-owl".to_string(),
+                "major".to_string(),
+                "Breaking".to_string(),
+            ],
+            vec!["seal-owl".to_string(), "patch".to_string(), "".to_string()],
+        ];
+
+        let table = format_table(&rows);
+
+        assert_eq!(
+            table,
+            "NAME         TYPE   SUMMARY\ndog-cat-owl  major  Breaking\nseal-owl     patch"
+        );
+    }
+
+    #[rstest]
+    #[case(chrono::TimeDelta::seconds(30), "just now")]
+    #[case(chrono::TimeDelta::minutes(1), "1 minute")]
+    #[case(chrono::TimeDelta::hours(5), "5 hours")]
+    #[case(chrono::TimeDelta::days(3), "3 days")]
+    #[case(chrono::TimeDelta::days(65), "2 months")]
+    #[case(chrono::TimeDelta::days(400), "1 year")]
+    fn test_format_age(#[case] duration: chrono::TimeDelta, #[case] expected: &str) {
+        assert_eq!(format_age(duration), expected);
+    }
+
+    #[rstest]
+    #[case("add-lists", true)]
+    #[case("v1.2-notes", true)]
+    #[case("", false)]
+    #[case("  ", false)]
+    #[case("../outside", false)]
+    #[case("nested/change", false)]
+    #[case("nested\\change", false)]
+    #[case("..", false)]
+    fn test_validate_file_stem(#[case] name: &str, #[case] valid: bool) {
+        assert_eq!(validate_file_stem(name).is_ok(), valid);
+    }
 }
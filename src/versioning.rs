@@ -0,0 +1,63 @@
+use crate::calver::bump_calver;
+use crate::changeset::{Bump, IncrementType};
+use crate::config::VersionScheme;
+use crate::plugin::{PluginInstance, bump_version_via_plugin};
+use chrono::{DateTime, Utc};
+use semver::Version;
+
+/// Determines the next version of the project according to the configured versioning scheme.
+/// The `publish_date` should be the same date the changelog is generated with
+pub fn bump_version(
+    scheme: &VersionScheme,
+    plugin: &mut PluginInstance,
+    current_version: &str,
+    bump_type: &IncrementType,
+    publish_date: &DateTime<Utc>,
+) -> anyhow::Result<String> {
+    match scheme {
+        VersionScheme::Semver => bump_semver(current_version, bump_type),
+        VersionScheme::Plugin => bump_version_via_plugin(plugin, current_version, bump_type),
+        VersionScheme::Calver { format } => bump_calver(format, current_version, publish_date),
+    }
+}
+
+fn bump_semver(current_version: &str, bump_type: &IncrementType) -> anyhow::Result<String> {
+    let current_version = Version::parse(current_version).map_err(|e| {
+        anyhow::anyhow!(
+            "The version `{current_version}` isn't a valid semantic version ({e}). Use the `plugin` versioning scheme for other version formats"
+        )
+    })?;
+
+    Ok(current_version.bump(bump_type).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("1.2.3", IncrementType::Major, "2.0.0")]
+    #[case("1.2.3", IncrementType::Minor, "1.3.0")]
+    #[case("1.2.3", IncrementType::Patch, "1.2.4")]
+    fn test_bump_semver(
+        #[case] current_version: &str,
+        #[case] bump_type: IncrementType,
+        #[case] expected: &str,
+    ) {
+        let result = bump_semver(current_version, &bump_type).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case("2024.01.1")]
+    #[case("1.2.3.4")]
+    #[case("1:2.3")]
+    fn test_bump_semver_rejects_non_semantic_versions(#[case] current_version: &str) {
+        let result = bump_semver(current_version, &IncrementType::Patch);
+
+        assert!(result.is_err());
+    }
+}
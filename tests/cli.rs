@@ -22,3 +22,615 @@ fn e2e_changeset_add() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn e2e_changeset_add_non_interactive() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+
+    let mut cmd = assert_cmd::Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("add")
+        .arg("--no-interactive")
+        .arg("-t")
+        .arg("minor")
+        .arg("-m")
+        .arg("Add lists")
+        .arg("--description-file")
+        .arg("-")
+        .arg("--name")
+        .arg("add-lists")
+        .arg("--package")
+        .arg("parser")
+        .arg("--json")
+        .write_stdin("Lists can now be nested.\n");
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "\"path\": \".changeset/add-lists.md\"",
+    ));
+
+    let contents = std::fs::read_to_string(tmp_dir.path().join(".changeset/add-lists.md"))?;
+    assert!(contents.contains("changeset/package: parser"));
+    assert!(contents.ends_with("# Add lists\n\nLists can now be nested.\n"));
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("add")
+        .arg("--no-interactive")
+        .arg("-t")
+        .arg("patch")
+        .arg("-m")
+        .arg("Fix lists")
+        .arg("--name")
+        .arg("add-lists");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("A changeset already exists at"));
+
+    for name in ["../outside", "nested/change", ""] {
+        let mut cmd = Command::cargo_bin("changeset")?;
+
+        cmd.current_dir(&tmp_dir)
+            .arg("add")
+            .arg("--no-interactive")
+            .arg("-t")
+            .arg("patch")
+            .arg("-m")
+            .arg("Fix lists")
+            .arg("--name")
+            .arg(name);
+
+        cmd.assert().failure();
+    }
+    assert!(!tmp_dir.path().join("outside.md").exists());
+    assert!(!tmp_dir.path().join(".changeset/nested").exists());
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_non_interactive_requires_input() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("add")
+        .arg("--no-interactive")
+        .arg("-t")
+        .arg("minor");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "The message is required, pass it with --message",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_edit_removes_emptied_changeset() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .env("VISUAL", "truncate -s 0")
+        .arg("add")
+        .arg("-t")
+        .arg("minor")
+        .arg("--edit");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("has been removed"));
+
+    let remaining = std::fs::read_dir(tmp_dir.path().join(".changeset"))?.count();
+    assert_eq!(remaining, 0);
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_edit_removes_changeset_when_editor_fails()
+-> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .env("VISUAL", "false")
+        .arg("add")
+        .arg("-t")
+        .arg("minor")
+        .arg("--edit");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("The editor `false` exited with"));
+
+    let remaining = std::fs::read_dir(tmp_dir.path().join(".changeset"))?.count();
+    assert_eq!(remaining, 0);
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_edit_removes_invalid_changeset() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let editor_script = tmp_dir.path().join("editor.sh");
+    std::fs::write(&editor_script, "printf 'Not a changeset\\n' > \"$1\"\n")?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    // Without a terminal the prompt to edit it again fails, as if it was declined
+    cmd.current_dir(&tmp_dir)
+        .env("VISUAL", format!("sh {}", editor_script.display()))
+        .arg("add")
+        .arg("-t")
+        .arg("minor")
+        .arg("--edit");
+
+    cmd.assert().failure();
+
+    let remaining = std::fs::read_dir(tmp_dir.path().join(".changeset"))?.count();
+    assert_eq!(remaining, 0);
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_edit_keeps_written_changeset() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let editor_script = tmp_dir.path().join("editor.sh");
+    std::fs::write(
+        &editor_script,
+        "printf 'Written in the editor\\n' >> \"$1\"\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .env("VISUAL", format!("sh {}", editor_script.display()))
+        .arg("add")
+        .arg("-t")
+        .arg("minor")
+        .arg("-m")
+        .arg("message")
+        .arg("--edit");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Changeset created at: "));
+
+    let changeset = std::fs::read_dir(tmp_dir.path().join(".changeset"))?
+        .next()
+        .unwrap()?;
+    let contents = std::fs::read_to_string(changeset.path())?;
+    assert!(contents.ends_with("# message\nWritten in the editor\n"));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_edit_preserves_description() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir(&changeset_dir)?;
+    std::fs::write(
+        changeset_dir.join("custom.md"),
+        "---\nchangeset/type: patch\n---\n\n# Old summary\n\nThe description.\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("edit")
+        .arg("custom")
+        .arg("-t")
+        .arg("minor")
+        .arg("-m")
+        .arg("New summary");
+
+    cmd.assert().success();
+
+    let contents = std::fs::read_to_string(changeset_dir.join("custom.md"))?;
+    assert_eq!(
+        contents,
+        "---\nchangeset/type: minor\n---\n\n# New summary\n\nThe description.\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_remove() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir(&changeset_dir)?;
+    std::fs::write(
+        changeset_dir.join("custom.md"),
+        "---\nchangeset/type: patch\n---\n\n# Summary\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("remove").arg("custom");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Removed"));
+    assert!(!changeset_dir.join("custom.md").exists());
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_remove_and_edit_by_relative_path() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir_all(changeset_dir.join("core"))?;
+    let markdown = "---\nchangeset/type: patch\n---\n\n# Summary\n";
+    std::fs::write(changeset_dir.join("fix.md"), markdown)?;
+    std::fs::write(changeset_dir.join("core/fix.md"), markdown)?;
+    std::fs::write(
+        changeset_dir.join("fix.toml"),
+        "bump_type = \"patch\"\nsummary = \"Summary\"\n",
+    )?;
+    std::fs::write(changeset_dir.join("broken.md"), "Not a changeset\n")?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("remove").arg("fix");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "The name `fix` refers to several changesets, pass one of: fix.md, fix.toml",
+    ));
+    assert!(changeset_dir.join("fix.md").exists());
+    assert!(changeset_dir.join("fix.toml").exists());
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("edit")
+        .arg("fix.md")
+        .arg("-m")
+        .arg("Edited");
+
+    cmd.assert().success();
+    let contents = std::fs::read_to_string(changeset_dir.join("fix.md"))?;
+    assert!(contents.contains("# Edited"));
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("remove")
+        .arg("fix.toml")
+        .arg("core/fix");
+
+    cmd.assert().success();
+    assert!(changeset_dir.join("fix.md").exists());
+    assert!(!changeset_dir.join("fix.toml").exists());
+    assert!(!changeset_dir.join("core/fix.md").exists());
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_lint_reports_every_invalid_file() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir(&changeset_dir)?;
+    std::fs::write(
+        changeset_dir.join("valid.md"),
+        "---\nchangeset/type: patch\n---\n\n# Summary\n",
+    )?;
+    std::fs::write(
+        changeset_dir.join("unknown-type.md"),
+        "---\nchangeset/type: huge\n---\n\n# Summary\n",
+    )?;
+    std::fs::write(
+        changeset_dir.join("no-heading.md"),
+        "---\nchangeset/type: patch\n---\n",
+    )?;
+    std::fs::write(
+        changeset_dir.join("duplicate.md"),
+        "---\nchangeset/type: minor\n---\n\n# Summary\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("lint");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown bump type `huge`"))
+        .stderr(predicate::str::contains("unknown-type.md:2"))
+        .stderr(predicate::str::contains("Missing a `# ` heading"))
+        .stderr(predicate::str::contains(
+            "The summary `Summary` is the same",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_toml_format() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).args([
+        "add",
+        "-t",
+        "minor",
+        "-m",
+        "Added a feature",
+        "--format",
+        "toml",
+    ]);
+
+    cmd.assert().success();
+    let changeset = std::fs::read_dir(tmp_dir.path().join(".changeset"))?
+        .next()
+        .unwrap()?
+        .path();
+    assert_eq!(changeset.extension().unwrap(), "toml");
+    let contents = std::fs::read_to_string(&changeset)?;
+    assert!(contents.contains("bump_type = \"minor\""));
+    assert!(contents.contains("summary = \"Added a feature\""));
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("lint");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 valid changeset(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_list_skips_ignored_files_and_searches_subdirectories()
+-> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir_all(changeset_dir.join("core"))?;
+    std::fs::write(
+        changeset_dir.join("README.md"),
+        "# Changesets\n\nRun `changeset add` to add a changeset.\n",
+    )?;
+    std::fs::write(
+        changeset_dir.join("core").join("nested.md"),
+        "---\nchangeset/type: minor\n---\n\n# Nested summary\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Nested summary"))
+        .stdout(predicate::str::contains("Changesets").not());
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_template() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let templates_dir = tmp_dir.path().join(".changeset").join("templates");
+    std::fs::create_dir_all(&templates_dir)?;
+    std::fs::write(
+        templates_dir.join("breaking.md"),
+        "## Migration guide\n\n{{summary}} requires the following changes:\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("add")
+        .arg("--no-interactive")
+        .arg("-t")
+        .arg("major")
+        .arg("-m")
+        .arg("Removed v1")
+        .arg("--template")
+        .arg("breaking")
+        .arg("--name")
+        .arg("removed-v1");
+
+    cmd.assert().success();
+
+    let contents = std::fs::read_to_string(tmp_dir.path().join(".changeset/removed-v1.md"))?;
+    assert!(contents.ends_with(
+        "# Removed v1\n\n## Migration guide\n\nRemoved v1 requires the following changes:\n"
+    ));
+
+    // Templates aren't pending changesets
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Removed v1"))
+        .stdout(predicate::str::contains("Migration guide").not());
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_template_rejects_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    std::fs::write(tmp_dir.path().join("outside.md"), "Outside\n")?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .arg("add")
+        .arg("--no-interactive")
+        .arg("-t")
+        .arg("major")
+        .arg("-m")
+        .arg("Removed v1")
+        .arg("--template")
+        .arg("../../outside");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("can't contain path separators"));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_add_template_edit_fills_in_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let templates_dir = tmp_dir.path().join(".changeset").join("templates");
+    std::fs::create_dir_all(&templates_dir)?;
+    std::fs::write(
+        templates_dir.join("breaking.md"),
+        "{{summary}} requires the following changes:\n",
+    )?;
+    let seen = tmp_dir.path().join("seen.md");
+    let editor_script = tmp_dir.path().join("editor.sh");
+    std::fs::write(
+        &editor_script,
+        format!(
+            "cp \"$1\" {}\nsed -i 's/^# $/# Removed v1/' \"$1\"\n",
+            seen.display()
+        ),
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir)
+        .env("VISUAL", format!("sh {}", editor_script.display()))
+        .arg("add")
+        .arg("-t")
+        .arg("major")
+        .arg("--template")
+        .arg("breaking")
+        .arg("--edit");
+
+    cmd.assert().success();
+
+    let contents = std::fs::read_to_string(seen)?;
+    assert!(contents.contains("{{summary}} requires the following changes:"));
+
+    let change_file = std::fs::read_dir(tmp_dir.path().join(".changeset"))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.is_file())
+        .expect("the changeset should be kept");
+    let contents = std::fs::read_to_string(change_file)?;
+    assert!(contents.contains("# Removed v1\n\nRemoved v1 requires the following changes:"));
+    assert!(!contents.contains("{{summary}}"));
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_from_commits() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(&tmp_dir)
+            .args([
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+            ])
+            .args(args)
+            .assert()
+            .success();
+    };
+    git(&["init", "--quiet"]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "chore: initial commit",
+    ]);
+    git(&["tag", "v1.0.0"]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "feat: add lists",
+    ]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "docs: update readme",
+    ]);
+    git(&[
+        "commit",
+        "--quiet",
+        "--allow-empty",
+        "-m",
+        "fix(parser): handle tabs",
+    ]);
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+    cmd.current_dir(&tmp_dir)
+        .args(["from-commits", "--since", "v1.0.0"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+    cmd.current_dir(&tmp_dir).args(["list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Add lists"))
+        .stdout(predicate::str::contains("Handle tabs"))
+        .stdout(predicate::str::contains("readme").not());
+
+    // Running again doesn't duplicate the existing changesets
+    let mut cmd = Command::cargo_bin("changeset")?;
+    cmd.current_dir(&tmp_dir)
+        .args(["from-commits", "--since", "v1.0.0"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped 2 change(s)"));
+    assert_eq!(
+        std::fs::read_dir(tmp_dir.path().join(".changeset"))?.count(),
+        2
+    );
+
+    Ok(())
+}
+
+#[test]
+fn e2e_changeset_list_skips_config() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp_dir = tempdir()?;
+    let changeset_dir = tmp_dir.path().join(".changeset");
+    std::fs::create_dir_all(&changeset_dir)?;
+    std::fs::write(
+        changeset_dir.join("config.json"),
+        r#"{"plugin": {"url": "gh:owner/repo@1.0.0"}, "changesets": {"directory": "./.changeset"}}"#,
+    )?;
+    std::fs::write(
+        changeset_dir.join("feature.json"),
+        r#"{"type": "minor", "summary": "Added a feature"}"#,
+    )?;
+
+    let mut cmd = Command::cargo_bin("changeset")?;
+
+    cmd.current_dir(&tmp_dir).arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Added a feature"));
+
+    Ok(())
+}